@@ -1,10 +1,16 @@
+use adv_code_2024::csv::CsvWriter;
+use adv_code_2024::error::InFile;
+use adv_code_2024::input::{for_each_line, open_input, InputOptions};
+use adv_code_2024::params::{Param, Params};
+use adv_code_2024::parse::Span;
 use adv_code_2024::*;
 use anyhow::*;
 use code_timing_macros::time_snippet;
 use const_format::concatcp;
+use itertools::{chain, Itertools};
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Cursor, Seek};
+use std::str::FromStr;
 
 const DAY: &str = "01";
 const INPUT_FILE: &str = concatcp!("input/", DAY, ".txt");
@@ -18,69 +24,250 @@ const TEST: &str = "\
 3   3
 ";
 
+// `explain` names a directory to write CSV breakdowns of each part's answer into.
+const PARAMS: &[Param] = &[("metric", "absolute"), ("explain", "")];
+
+/// How one location list is scored against another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Metric {
+    /// Sum of the distances between sorted pairs.
+    Absolute,
+    /// Sum of the squared distances between sorted pairs.
+    Squared,
+    /// Largest distance between sorted pairs.
+    Max,
+    /// Each value in one list weighted by how often it appears in the other.
+    Similarity,
+}
+
+impl FromStr for Metric {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "absolute" => Ok(Metric::Absolute),
+            "squared" => Ok(Metric::Squared),
+            "max" => Ok(Metric::Max),
+            "similarity" => Ok(Metric::Similarity),
+            _ => bail!("unknown metric, expected absolute, squared, max or similarity"),
+        }
+    }
+}
+
+impl Metric {
+    /// What the pair at each rank adds to the score, or `None` where that doesn't fit in a
+    /// `usize`. For `Max` it's the distance the maximum is taken over. Both lists must already be
+    /// sorted.
+    fn contributions<'a>(
+        self,
+        a: &'a [usize],
+        b: &'a [usize],
+    ) -> impl Iterator<Item = Option<usize>> + 'a {
+        let counts = match self {
+            Metric::Similarity => b.iter().counts(),
+            _ => HashMap::new(),
+        };
+        a.iter().zip(b).map(move |(a, b)| {
+            let distance = a.abs_diff(*b);
+            match self {
+                Metric::Absolute | Metric::Max => Some(distance),
+                Metric::Squared => distance.checked_mul(distance),
+                Metric::Similarity => a.checked_mul(*counts.get(a).unwrap_or(&0)),
+            }
+        })
+    }
+
+    /// Both lists must already be sorted. Fails if the score doesn't fit in a `usize`.
+    fn score(self, a: &[usize], b: &[usize]) -> Result<usize> {
+        let mut contributions = self.contributions(a, b);
+        let score = match self {
+            Metric::Max => contributions.try_fold(0, |max, c| Some(max.max(c?))),
+            _ => contributions.try_fold(0usize, |sum, c| sum.checked_add(c?)),
+        };
+        score.ok_or_else(|| self.too_large())
+    }
+
+    fn too_large(self) -> Error {
+        anyhow!("{:?} score doesn't fit in a usize", self)
+    }
+}
+
 fn main() -> Result<()> {
     start_day(DAY);
+    let params = Params::from_args(PARAMS)?;
 
     //region Part 1
     println!("=== Part 1 ===");
 
-    fn parse_int(val: &str) -> usize {
-        usize::from_str_radix(val, 10).unwrap()
+    fn for_each_row<R: BufRead>(
+        reader: R,
+        mut f: impl FnMut(&[usize]) -> Result<()>,
+    ) -> Result<()> {
+        let mut row = Vec::new();
+        let mut columns = None;
+        for_each_line(reader, &InputOptions::default(), |line_number, line| {
+            let span = Span::line(line_number, line);
+            row.clear();
+            for value in span.uints() {
+                row.push(value?);
+            }
+            match columns {
+                None if row.len() < 2 => bail!(span.error("at least two columns")),
+                None => columns = Some(row.len()),
+                Some(columns) if columns != row.len() => {
+                    bail!(span.error(format!("{} columns", columns)))
+                }
+                Some(_) => {}
+            }
+            f(&row)
+        })
     }
 
-    fn parse_file<R: BufRead>(reader: R) -> (Vec<usize>, Vec<usize>) {
-        reader
-            .lines()
-            .flatten()
-            .map(|line| {
-                let (left, right) = line.split_at(line.find("   ").unwrap());
-                (parse_int(left), parse_int(&right[3..]))
-            })
-            .unzip()
+    fn parse_columns<R: BufRead>(reader: R) -> Result<Vec<Vec<usize>>> {
+        let mut columns: Vec<Vec<usize>> = Vec::new();
+        for_each_row(reader, |row| {
+            columns.resize_with(row.len(), Vec::new);
+            for (column, value) in columns.iter_mut().zip(row) {
+                column.push(*value);
+            }
+            Ok(())
+        })?;
+        Ok(columns)
     }
 
-    fn part1<R: BufRead>(reader: R) -> Result<usize> {
-        let (mut left_list, mut right_list) = parse_file(reader);
-        left_list.sort();
-        right_list.sort();
-        let answer = left_list
+    /// `metric` applied to every pair of sorted columns.
+    fn distance_matrix(columns: &[Vec<usize>], metric: Metric) -> Result<Vec<Vec<usize>>> {
+        columns
             .iter()
-            .zip(right_list)
-            .map(|(left, right)| left.abs_diff(right))
-            .sum();
-        Ok(answer)
+            .map(|a| columns.iter().map(|b| metric.score(a, b)).collect())
+            .collect()
     }
 
-    assert_eq!(11, part1(BufReader::new(TEST.as_bytes()))?);
+    fn explain_path(params: &Params, part: &str) -> Result<Option<String>> {
+        let dir: String = params.get("explain")?;
+        Ok((!dir.is_empty()).then(|| format!("{}/day{}-{}.csv", dir, DAY, part)))
+    }
 
-    let input_file = BufReader::new(File::open(INPUT_FILE)?);
-    let result = time_snippet!(part1(input_file)?);
+    /// One row per rank with every column's value there and what the first two columns' pair
+    /// there contributes to the score under `metric`.
+    fn explain_pairs(columns: &[Vec<usize>], metric: Metric, path: &str) -> Result<()> {
+        let mut csv = CsvWriter::create(path)?;
+        let header = (1..=columns.len()).map(|i| format!("column {}", i));
+        csv.record(chain!(
+            ["rank".to_owned()],
+            header,
+            ["contribution".to_owned()]
+        ))?;
+        let contributions = metric.contributions(&columns[0], &columns[1]);
+        for (rank, contribution) in contributions.enumerate() {
+            let values = columns.iter().map(|column| column[rank]);
+            let contribution = contribution.ok_or_else(|| metric.too_large())?;
+            csv.record(chain!([rank + 1], values, [contribution]))?;
+        }
+        csv.finish()?;
+        println!("Wrote {}", path);
+        Ok(())
+    }
+
+    /// Every column's score against every other, first row and column being column numbers.
+    fn explain_matrix(matrix: &[Vec<usize>], path: &str) -> Result<()> {
+        let mut csv = CsvWriter::create(path)?;
+        let header = (1..=matrix.len()).map(|i| format!("column {}", i));
+        csv.record(chain!(["".to_owned()], header))?;
+        for (i, row) in matrix.iter().enumerate() {
+            csv.record(chain!(
+                [format!("column {}", i + 1)],
+                row.iter().map(usize::to_string)
+            ))?;
+        }
+        csv.finish()?;
+        println!("Wrote {}", path);
+        Ok(())
+    }
+
+    fn part1<R: BufRead>(reader: R, params: &Params) -> Result<usize> {
+        let mut columns = parse_columns(reader)?;
+        ensure!(
+            columns.len() >= 2,
+            "expected at least two columns, found {}",
+            columns.len()
+        );
+        for column in &mut columns {
+            column.sort();
+        }
+        let metric: Metric = params.get("metric")?;
+        if let Some(path) = explain_path(params, "part1")? {
+            explain_pairs(&columns, metric, &path)?;
+        }
+        if let Some(path) = explain_path(params, "part1-matrix")? {
+            explain_matrix(&distance_matrix(&columns, metric)?, &path)?;
+        }
+        metric.score(&columns[0], &columns[1])
+    }
+
+    assert_eq!(
+        11,
+        part1(BufReader::new(TEST.as_bytes()), &Params::new(PARAMS))?
+    );
+
+    let result = time_snippet!(part1(open_input(INPUT_FILE)?, &params)
+        .in_file(INPUT_FILE)
+        .context(concatcp!("day ", DAY, " part 1"))?);
     println!("Result = {}", result);
     //endregion
 
     //region Part 2
     println!("\n=== Part 2 ===");
 
-    fn part2<R: BufRead>(reader: R) -> Result<usize> {
-        let (left_list, right_list) = parse_file(reader);
-        let mut similarity_score = 0;
+    /// Only compares the first column against the second, so more than two is an error.
+    fn part2<R: BufRead + Seek>(mut reader: R, params: &Params) -> Result<usize> {
         let mut right_counts: HashMap<usize, usize> = HashMap::new();
+        for_each_row(&mut reader, |row| {
+            ensure!(
+                row.len() == 2,
+                "similarity compares exactly two columns, found {}",
+                row.len()
+            );
+            *right_counts.entry(row[1]).or_default() += 1;
+            Ok(())
+        })?;
 
-        for element in right_list {
-            right_counts.insert(element, *right_counts.get(&element).unwrap_or(&0) + 1);
-        }
+        let explain_path = explain_path(params, "part2")?;
+        let mut csv = match &explain_path {
+            Some(path) => {
+                let mut csv = CsvWriter::create(path)?;
+                csv.record(["line", "left", "right count", "contribution"])?;
+                Some(csv)
+            }
+            None => None,
+        };
 
-        for element in left_list {
-            similarity_score += right_counts.get(&element).unwrap_or(&0) * element;
-        }
+        reader.rewind()?;
+        let mut similarity_score = 0;
+        let mut line = 0;
+        for_each_row(reader, |row| {
+            line += 1;
+            let count = right_counts.get(&row[0]).unwrap_or(&0);
+            let contribution = count * row[0];
+            if let Some(csv) = &mut csv {
+                csv.record([line, row[0], *count, contribution])?;
+            }
+            similarity_score += contribution;
+            Ok(())
+        })?;
 
+        if let (Some(csv), Some(path)) = (csv, explain_path) {
+            csv.finish()?;
+            println!("Wrote {}", path);
+        }
         Ok(similarity_score)
     }
 
-    assert_eq!(31, part2(BufReader::new(TEST.as_bytes()))?);
+    assert_eq!(31, part2(Cursor::new(TEST), &Params::new(PARAMS))?);
 
-    let input_file = BufReader::new(File::open(INPUT_FILE)?);
-    let result = time_snippet!(part2(input_file)?);
+    let result = time_snippet!(part2(open_input(INPUT_FILE)?, &params)
+        .in_file(INPUT_FILE)
+        .context(concatcp!("day ", DAY, " part 2"))?);
     println!("Result = {}", result);
     //endregion
 
@@ -1,11 +1,15 @@
+use adv_code_2024::error::InFile;
+use adv_code_2024::input::load_input;
+use adv_code_2024::params::{Param, Params};
+use adv_code_2024::parse::{ParseError, Span};
 use adv_code_2024::*;
 use anyhow::*;
 use code_timing_macros::time_snippet;
 use const_format::concatcp;
 use itertools::Itertools;
-use std::fs::File;
+use std::fmt::Display;
 use std::io::{BufRead, BufReader};
-use std::ops::Range;
+use std::ops::RangeInclusive;
 
 const DAY: &str = "02";
 const INPUT_FILE: &str = concatcp!("input/", DAY, ".txt");
@@ -19,22 +23,32 @@ const TEST: &str = "\
 1 3 6 7 9
 ";
 
+// Levels must change by `min_step..=max_step` each time, and the problem dampener may drop up to
+// `dampener` of them. `annotate` prints every report with why it is or isn't safe.
+const PARAMS: &[Param] = &[
+    ("min_step", "1"),
+    ("max_step", "3"),
+    ("dampener", "1"),
+    ("annotate", "false"),
+];
+
 fn main() -> Result<()> {
     start_day(DAY);
+    let params = Params::from_args(PARAMS)?;
+    let (input, warning) = load_input(INPUT_FILE)?;
+    if let Some(warning) = warning {
+        eprintln!("warning: {}: {}", INPUT_FILE, warning);
+    }
 
     //region Part 1
     println!("=== Part 1 ===");
 
-    #[derive(PartialEq, Debug)]
+    #[derive(Clone, Copy, PartialEq, Debug)]
     enum ChangeType {
         Increasing,
         Decreasing,
     }
 
-    fn get_diff_between_elements(parts: &Vec<i32>, a: usize, b: usize) -> Option<i32> {
-        Some(parts.get(b)? - parts.get(a)?)
-    }
-
     fn get_change_type(diff: i32) -> Option<ChangeType> {
         if diff < 0 {
             Some(ChangeType::Decreasing)
@@ -45,96 +59,229 @@ fn main() -> Result<()> {
         }
     }
 
-    fn parse_levels_from_report(line: &String) -> Vec<i32> {
-        line.split(' ')
-            .map(|level| i32::from_str_radix(level, 10).unwrap())
+    fn parse_levels_from_report(line: Span) -> Result<Vec<i32>, ParseError> {
+        line.ints().collect()
+    }
+
+    fn parse<R: BufRead>(reader: R) -> Result<Vec<Vec<i32>>> {
+        reader
+            .lines()
+            .enumerate()
+            .map(|(i, line)| Ok(parse_levels_from_report(Span::line(i + 1, &line?))?))
             .collect()
     }
 
-    fn report_is_safe(levels: &Vec<i32>) -> bool {
-        let change_type = match get_change_type(get_diff_between_elements(&levels, 0, 1).unwrap()) {
-            Some(x) => x,
-            None => return false,
-        };
+    fn step_bounds(params: &Params) -> Result<RangeInclusive<u32>> {
+        Ok(params.get("min_step")?..=params.get("max_step")?)
+    }
 
-        for i in (Range {
-            start: 0,
-            end: levels.len() - 1,
-        }) {
-            let diff_to_next = get_diff_between_elements(&levels, i, i + 1).unwrap();
-
-            match get_change_type(diff_to_next) {
-                Some(this_change) => {
-                    if this_change != change_type {
-                        return false;
-                    }
-                }
-                None => {
-                    return false;
-                }
+    /// Why a step between two levels isn't allowed.
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    enum Violation {
+        /// Goes the other way to the report's first step.
+        DirectionChange,
+        ZeroStep,
+        StepTooSmall(u32),
+        StepTooLarge(u32),
+    }
+
+    impl Display for Violation {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Violation::DirectionChange => write!(f, "changes direction"),
+                Violation::ZeroStep => write!(f, "doesn't change"),
+                Violation::StepTooSmall(step) => write!(f, "step of {} is too small", step),
+                Violation::StepTooLarge(step) => write!(f, "step of {} is too large", step),
             }
+        }
+    }
 
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    enum Verdict {
+        Safe,
+        /// `levels[index]` is the first level that can't follow the one before it.
+        Unsafe {
+            index: usize,
+            violation: Violation,
+        },
+    }
+
+    fn check_report(levels: &[i32], steps: &RangeInclusive<u32>) -> Verdict {
+        let mut change_type = None;
+        for (index, pair) in levels.windows(2).enumerate() {
+            let diff_to_next = pair[1] - pair[0];
             let abs_diff = diff_to_next.unsigned_abs();
-            if !(abs_diff >= 1 && abs_diff <= 3) {
-                return false;
+            let unsafe_because = |violation| Verdict::Unsafe {
+                index: index + 1,
+                violation,
+            };
+
+            let this_change = match get_change_type(diff_to_next) {
+                Some(this_change) => this_change,
+                None => return unsafe_because(Violation::ZeroStep),
+            };
+            if *change_type.get_or_insert(this_change) != this_change {
+                return unsafe_because(Violation::DirectionChange);
+            }
+
+            if abs_diff < *steps.start() {
+                return unsafe_because(Violation::StepTooSmall(abs_diff));
+            }
+            if abs_diff > *steps.end() {
+                return unsafe_because(Violation::StepTooLarge(abs_diff));
             }
         }
-        true
+        Verdict::Safe
     }
 
-    fn part1<R: BufRead>(reader: R) -> Result<usize> {
-        let lines = reader
-            .lines()
-            .flatten()
-            .filter(|line| report_is_safe(&parse_levels_from_report(line)));
+    fn part1<R: BufRead>(reader: R, params: &Params) -> Result<usize> {
+        let reports = parse(reader)?;
+        let steps = step_bounds(params)?;
+        let annotate: bool = params.get("annotate")?;
 
-        Ok(lines.count())
+        let mut safe = 0;
+        for levels in &reports {
+            let verdict = check_report(levels, &steps);
+            if annotate {
+                match verdict {
+                    Verdict::Safe => println!("{}: safe", levels.iter().join(" ")),
+                    Verdict::Unsafe { index, violation } => println!(
+                        "{}: unsafe, level {} ({} -> {}) {}",
+                        levels.iter().join(" "),
+                        index + 1,
+                        levels[index - 1],
+                        levels[index],
+                        violation
+                    ),
+                }
+            }
+            if verdict == Verdict::Safe {
+                safe += 1;
+            }
+        }
+        Ok(safe)
     }
 
-    assert_eq!(2, part1(BufReader::new(TEST.as_bytes()))?);
+    assert_eq!(
+        2,
+        part1(BufReader::new(TEST.as_bytes()), &Params::new(PARAMS))?
+    );
 
-    let input_file = BufReader::new(File::open(INPUT_FILE)?);
-    let result = time_snippet!(part1(input_file)?);
+    let result = time_snippet!(part1(BufReader::new(input.as_bytes()), &params)
+        .in_file(INPUT_FILE)
+        .context(concatcp!("day ", DAY, " part 1"))?);
     println!("Result = {}", result);
     //endregion
 
     //region Part 2
     println!("\n=== Part 2 ===");
 
-    fn dropped_level_variants(levels: Vec<i32>) -> Vec<Vec<i32>> {
-        let mut variants = Vec::new();
-        for i in (Range {
-            start: 0,
-            end: levels.len(),
-        }) {
-            let pre = &levels[..i];
-            let post = &levels[i + 1..];
-            variants.push(pre.iter().chain(post).cloned().collect_vec());
+    /// The fewest levels to drop from a report so every remaining step goes `direction` by an
+    /// amount in `steps`, or `None` if that takes more than `max_removals`. Each kept level can
+    /// only follow one of the `max_removals + 1` levels before it, so this is linear for a fixed
+    /// dampener.
+    fn removals_in_direction(
+        levels: &[i32],
+        direction: ChangeType,
+        steps: &RangeInclusive<u32>,
+        max_removals: usize,
+    ) -> Option<Vec<usize>> {
+        let fits = |from: i32, to: i32| {
+            get_change_type(to - from) == Some(direction)
+                && steps.contains(&(to - from).unsigned_abs())
+        };
+
+        // best[j]: fewest levels dropped from levels[..=j] when levels[j] is kept, and the kept
+        // level before it
+        let mut best: Vec<(usize, Option<usize>)> = Vec::with_capacity(levels.len());
+        for j in 0..levels.len() {
+            let from_start = (j, None);
+            let entry = (j.saturating_sub(max_removals + 1)..j)
+                .filter(|&i| fits(levels[i], levels[j]))
+                .map(|i| (best[i].0 + (j - i - 1), Some(i)))
+                .fold(from_start, |a, b| if b.0 < a.0 { b } else { a });
+            best.push(entry);
         }
-        variants
-    }
 
-    fn part2<R: BufRead>(reader: R) -> Result<usize> {
-        let lines = reader.lines().flatten().filter(|line| {
-            let levels = parse_levels_from_report(line);
+        let last = levels.len().checked_sub(1)?;
+        let (removed, end) = best
+            .iter()
+            .enumerate()
+            .map(|(j, (removed, _))| (removed + (last - j), j))
+            .min()?;
+        if removed > max_removals {
+            return None;
+        }
 
-            let is_safe_natively = report_is_safe(&levels);
-            if is_safe_natively {
-                return true;
-            }
+        let mut kept = vec![false; levels.len()];
+        let mut at = Some(end);
+        while let Some(j) = at {
+            kept[j] = true;
+            at = best[j].1;
+        }
+        Some((0..levels.len()).filter(|&i| !kept[i]).collect())
+    }
 
-            let variants = dropped_level_variants(levels);
+    /// Indices of the fewest levels to drop to make a report safe, if it takes at most
+    /// `max_removals`.
+    fn levels_to_drop(
+        levels: &[i32],
+        steps: &RangeInclusive<u32>,
+        max_removals: usize,
+    ) -> Option<Vec<usize>> {
+        if levels.is_empty() {
+            return Some(Vec::new());
+        }
+        [ChangeType::Increasing, ChangeType::Decreasing]
+            .into_iter()
+            .filter_map(|direction| removals_in_direction(levels, direction, steps, max_removals))
+            .min_by_key(Vec::len)
+    }
 
-            variants.into_iter().any(|report| report_is_safe(&report))
-        });
+    fn part2<R: BufRead>(reader: R, params: &Params) -> Result<usize> {
+        let reports = parse(reader)?;
+        let steps = step_bounds(params)?;
+        let dampener = params.get("dampener")?;
+        let annotate: bool = params.get("annotate")?;
 
-        Ok(lines.count())
+        let mut safe = 0;
+        for levels in &reports {
+            let dropped = levels_to_drop(levels, &steps, dampener);
+            if annotate {
+                let report = levels.iter().join(" ");
+                match &dropped {
+                    Some(dropped) if dropped.is_empty() => println!("{}: safe", report),
+                    Some(dropped) => println!(
+                        "{}: safe without level {}",
+                        report,
+                        dropped
+                            .iter()
+                            .map(|&i| format!("{} ({})", i + 1, levels[i]))
+                            .join(", ")
+                    ),
+                    None => println!(
+                        "{}: unsafe even after dropping {} level{}",
+                        report,
+                        dampener,
+                        if dampener == 1 { "" } else { "s" }
+                    ),
+                }
+            }
+            if dropped.is_some() {
+                safe += 1;
+            }
+        }
+        Ok(safe)
     }
 
-    assert_eq!(4, part2(BufReader::new(TEST.as_bytes()))?);
+    assert_eq!(
+        4,
+        part2(BufReader::new(TEST.as_bytes()), &Params::new(PARAMS))?
+    );
 
-    let input_file = BufReader::new(File::open(INPUT_FILE)?);
-    let result = time_snippet!(part2(input_file)?);
+    let result = time_snippet!(part2(BufReader::new(input.as_bytes()), &params)
+        .in_file(INPUT_FILE)
+        .context(concatcp!("day ", DAY, " part 2"))?);
     println!("Result = {}", result);
     //endregion
 
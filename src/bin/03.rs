@@ -1,10 +1,13 @@
+use adv_code_2024::input::open_input;
+use adv_code_2024::params::{Param, Params};
 use adv_code_2024::*;
 use anyhow::*;
 use code_timing_macros::time_snippet;
 use const_format::concatcp;
-use regex::Regex;
-use std::fs::File;
+use std::collections::VecDeque;
+use std::fmt::Display;
 use std::io::{BufRead, BufReader};
+use std::ops::Range;
 
 const DAY: &str = "03";
 const INPUT_FILE: &str = concatcp!("input/", DAY, ".txt");
@@ -17,110 +20,461 @@ const TEST2: &str = "\
 xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))
 ";
 
-fn main() -> Result<()> {
-    start_day(DAY);
+const TEST3: &str = "\
+mul(2,4)don't()
+mul(5,5)mul(1000,2)
+do()mul(11,8)
+";
 
-    //region Part 1
-    println!("=== Part 1 ===");
+// `extended` also runs `add(a,b)` and `sub(a,b)`, and `nested` makes every `don't()` need its own
+// `do()` before instructions count again. Calls with operands longer than `max_digits` are
+// corrupted, unless it's `any`. `visualize` re-prints the input marked up as `ansi` or `html`,
+// followed by every instruction with the running total.
+const PARAMS: &[Param] = &[
+    ("extended", "false"),
+    ("nested", "false"),
+    ("max_digits", "3"),
+    ("visualize", "none"),
+];
+
+/// What the interpreter keeps track of while it runs.
+#[derive(Debug, Default)]
+struct Machine {
+    total: i64,
+    /// How many `don't()`s are still open.
+    disabled: usize,
+}
 
-    struct MulCall {
-        left: i32,
-        right: i32,
+impl Machine {
+    fn enabled(&self) -> bool {
+        self.disabled == 0
     }
 
-    impl MulCall {
-        fn from(l_str: &str, r_str: &str) -> Result<Self> {
-            Ok(Self {
-                left: l_str.parse()?,
-                right: r_str.parse()?,
-            })
+    /// Adds `operation` applied to both operands to the total, unless instructions are disabled.
+    fn accumulate(
+        &mut self,
+        name: &str,
+        operation: fn(i64, i64) -> Option<i64>,
+        args: &[i64],
+    ) -> Result<()> {
+        if self.enabled() {
+            self.total = operation(args[0], args[1])
+                .and_then(|value| self.total.checked_add(value))
+                .with_context(|| format!("{} result doesn't fit in 64 bits", name))?;
         }
+        Ok(())
+    }
+}
+
+/// Something the interpreter can run, written in the input as `name(arg,...)`.
+struct Instruction {
+    name: &'static str,
+    arity: usize,
+    /// Switches other instructions on or off rather than computing anything.
+    control: bool,
+    run: fn(&mut Machine, &[i64]) -> Result<()>,
+}
+
+const MUL: Instruction = Instruction {
+    name: "mul",
+    arity: 2,
+    control: false,
+    run: |machine, args| machine.accumulate("mul", i64::checked_mul, args),
+};
+
+const ADD: Instruction = Instruction {
+    name: "add",
+    arity: 2,
+    control: false,
+    run: |machine, args| machine.accumulate("add", i64::checked_add, args),
+};
+
+const SUB: Instruction = Instruction {
+    name: "sub",
+    arity: 2,
+    control: false,
+    run: |machine, args| machine.accumulate("sub", i64::checked_sub, args),
+};
+
+const DO: Instruction = Instruction {
+    name: "do",
+    arity: 0,
+    control: true,
+    run: |machine, _| {
+        machine.disabled = 0;
+        Ok(())
+    },
+};
+
+const DONT: Instruction = Instruction {
+    name: "don't",
+    arity: 0,
+    control: true,
+    run: |machine, _| {
+        machine.disabled = 1;
+        Ok(())
+    },
+};
+
+const NESTED_DO: Instruction = Instruction {
+    name: "do",
+    arity: 0,
+    control: true,
+    run: |machine, _| {
+        machine.disabled = machine.disabled.saturating_sub(1);
+        Ok(())
+    },
+};
+
+const NESTED_DONT: Instruction = Instruction {
+    name: "don't",
+    arity: 0,
+    control: true,
+    run: |machine, _| {
+        machine.disabled += 1;
+        Ok(())
+    },
+};
+
+/// A well-formed call to a known instruction.
+struct Token<'i> {
+    /// Byte offset of the instruction name in the input.
+    offset: usize,
+    /// Byte length of the call, from the name through `)`.
+    len: usize,
+    instruction: &'i Instruction,
+    args: Vec<i64>,
+}
+
+/// The instructions a program may use and how their calls are written.
+struct Dialect {
+    instructions: Vec<Instruction>,
+    /// Longest operand a call can have before it counts as corrupted.
+    max_digits: Option<usize>,
+}
+
+/// Lazily picks calls to a dialect's instructions out of a byte stream, skipping everything else.
+/// Line breaks are just more bytes, so nothing resets between lines.
+struct Scanner<'i, R> {
+    reader: R,
+    /// Bytes read from `reader` but not yet scanned past.
+    lookahead: VecDeque<u8>,
+    /// Byte offset of `lookahead[0]`.
+    offset: usize,
+    dialect: &'i Dialect,
+}
 
-        fn call(self) -> i32 {
-            self.left * self.right
+impl<'i, R: BufRead> Scanner<'i, R> {
+    fn new(reader: R, dialect: &'i Dialect) -> Self {
+        Self {
+            reader,
+            lookahead: VecDeque::new(),
+            offset: 0,
+            dialect,
         }
     }
 
-    enum Operation {
-        Do,
-        Dont,
-        Mul(MulCall),
+    fn peek(&mut self, i: usize) -> Result<Option<u8>> {
+        while self.lookahead.len() <= i {
+            let buffer = self.reader.fill_buf()?;
+            let Some(&byte) = buffer.first() else {
+                return Ok(None);
+            };
+            self.reader.consume(1);
+            self.lookahead.push_back(byte);
+        }
+        Ok(Some(self.lookahead[i]))
     }
 
-    fn part1<R: BufRead>(reader: R) -> Result<i32> {
-        let mul_re = Regex::new(r"mul\((\d+),(\d+)\)").unwrap();
-        let v = reader
-            .lines()
-            .flatten()
-            .flat_map(|line| {
-                mul_re
-                    .captures_iter(&line)
-                    .map(|c| c.extract())
-                    .map(|(_, [l_str, r_str])| MulCall::from(l_str, r_str).unwrap())
-                    .collect::<Vec<_>>()
-            })
-            .map(|mul| mul.call())
-            .sum();
-        Ok(v)
+    fn advance(&mut self, n: usize) {
+        self.lookahead.drain(..n);
+        self.offset += n;
     }
 
-    assert_eq!(161, part1(BufReader::new(TEST1.as_bytes()))?);
+    /// The arguments of a call whose `(` is at `open`, and the length up to its `)`.
+    fn args_at(&mut self, open: usize) -> Result<Option<(Vec<i64>, usize)>> {
+        let mut args = Vec::new();
+        let mut i = open + 1;
+        if self.peek(i)? == Some(b')') {
+            return Ok(Some((args, i + 1)));
+        }
+        loop {
+            let start = i;
+            let mut value: i64 = 0;
+            while let Some(digit @ b'0'..=b'9') = self.peek(i)? {
+                if self.dialect.max_digits == Some(i - start) {
+                    return Ok(None);
+                }
+                value = value
+                    .checked_mul(10)
+                    .and_then(|value| value.checked_add(i64::from(digit - b'0')))
+                    .with_context(|| {
+                        format!("operand at byte {} is too large", self.offset + start)
+                    })?;
+                i += 1;
+            }
+            if i == start {
+                return Ok(None);
+            }
+            args.push(value);
+            match self.peek(i)? {
+                Some(b',') => i += 1,
+                Some(b')') => return Ok(Some((args, i + 1))),
+                _ => return Ok(None),
+            }
+        }
+    }
 
-    let input_file = BufReader::new(File::open(INPUT_FILE)?);
-    let result = time_snippet!(part1(input_file)?);
-    println!("Result = {}", result);
-    //endregion
+    fn next_token(&mut self) -> Result<Option<Token<'i>>> {
+        while self.peek(0)?.is_some() {
+            if let Some(token) = self.call()? {
+                self.advance(token.len);
+                return Ok(Some(token));
+            }
+            self.advance(1);
+        }
+        Ok(None)
+    }
 
-    //region Part 2
-    println!("\n=== Part 2 ===");
+    /// The call starting right at the front of the lookahead, if there is one.
+    fn call(&mut self) -> Result<Option<Token<'i>>> {
+        for instruction in &self.dialect.instructions {
+            let name = instruction.name.as_bytes();
+            let mut matches = true;
+            for (i, &expected) in name.iter().enumerate() {
+                if self.peek(i)? != Some(expected) {
+                    matches = false;
+                    break;
+                }
+            }
+            if !matches || self.peek(name.len())? != Some(b'(') {
+                continue;
+            }
+            if let Some((args, len)) = self.args_at(name.len())? {
+                if args.len() == instruction.arity {
+                    let token = Token {
+                        offset: self.offset,
+                        len,
+                        instruction,
+                        args,
+                    };
+                    return Ok(Some(token));
+                }
+            }
+        }
+        Ok(None)
+    }
+}
 
-    fn part2<R: BufRead>(reader: R) -> Result<i32> {
-        let op_re = Regex::new(r"(do(?:n't)?)\(()()\)|(mul)\((\d+),(\d+)\)").unwrap();
-        let operations = reader.lines().flatten().flat_map(|line| {
-            op_re
-                .captures_iter(&line)
-                .map(|c| c.extract())
-                .map(|(_, [operation, l_str, r_str])| match operation {
-                    "do" => Operation::Do,
-                    "don't" => Operation::Dont,
-                    "mul" => Operation::Mul(MulCall::from(l_str, r_str).unwrap()),
-                    _ => panic!("Unknown operation found"),
-                })
-                .collect::<Vec<_>>()
-        });
-
-        enum OperationResult {
-            Enabled(i32),
-            Disabled(i32),
+impl<'i, R: BufRead> Iterator for Scanner<'i, R> {
+    type Item = Result<Token<'i>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_token().transpose()
+    }
+}
+
+/// What running an instruction did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Executed,
+    /// Not run because a `don't()` was in effect.
+    Skipped,
+    Control,
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Executed => write!(f, "executed"),
+            Status::Skipped => write!(f, "skipped"),
+            Status::Control => write!(f, "control"),
         }
+    }
+}
 
-        impl From<OperationResult> for i32 {
-            fn from(value: OperationResult) -> Self {
-                match value {
-                    OperationResult::Enabled(v) => v,
-                    OperationResult::Disabled(v) => v,
-                }
+/// Runs every instruction in the input, telling `observe` about each one as it goes.
+fn run<R: BufRead>(
+    reader: R,
+    dialect: &Dialect,
+    mut observe: impl FnMut(&Token, Status, &Machine),
+) -> Result<Machine> {
+    let mut machine = Machine::default();
+    for token in Scanner::new(reader, dialect) {
+        let token = token?;
+        let status = match (token.instruction.control, machine.enabled()) {
+            (true, _) => Status::Control,
+            (false, true) => Status::Executed,
+            (false, false) => Status::Skipped,
+        };
+        (token.instruction.run)(&mut machine, &token.args).with_context(|| {
+            format!(
+                "running {} at byte {}",
+                token.instruction.name, token.offset
+            )
+        })?;
+        observe(&token, status, &machine);
+    }
+    Ok(machine)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Markup {
+    Ansi,
+    Html,
+}
+
+/// A run of input bytes and what the interpreter made of it; `None` means it was ignored.
+type Region = (Range<usize>, Option<Status>);
+
+/// The input with every region marked up, ignored bytes included.
+fn render(input: &[u8], instructions: &[Region], markup: Markup) -> String {
+    let mut regions = Vec::new();
+    let mut end = 0;
+    for (range, status) in instructions {
+        if range.start > end {
+            regions.push((end..range.start, None));
+        }
+        regions.push((range.clone(), *status));
+        end = range.end;
+    }
+    if end < input.len() {
+        regions.push((end..input.len(), None));
+    }
+
+    let mut rendered = String::new();
+    if markup == Markup::Html {
+        rendered.push_str("<pre>");
+    }
+    for (range, status) in regions {
+        let text = String::from_utf8_lossy(&input[range]);
+        let class = match status {
+            Some(Status::Executed) => "executed",
+            Some(Status::Skipped) => "skipped",
+            Some(Status::Control) => "control",
+            None => "ignored",
+        };
+        match markup {
+            Markup::Ansi => {
+                let color = match status {
+                    Some(Status::Executed) => "32",
+                    Some(Status::Skipped) => "31",
+                    Some(Status::Control) => "36",
+                    None => "2",
+                };
+                rendered.push_str(&format!("\x1b[{}m{}\x1b[0m", color, text));
+            }
+            Markup::Html => {
+                let text = text
+                    .replace('&', "&amp;")
+                    .replace('<', "&lt;")
+                    .replace('>', "&gt;");
+                rendered.push_str(&format!("<span class=\"{}\">{}</span>", class, text));
             }
         }
+    }
+    if markup == Markup::Html {
+        rendered.push_str("</pre>");
+    }
+    rendered
+}
+
+/// Runs the program, printing the visualization asked for by `params` if there is one.
+fn execute<R: BufRead>(mut reader: R, dialect: &Dialect, params: &Params) -> Result<i64> {
+    let markup = match params.get::<String>("visualize")?.as_str() {
+        "none" => return Ok(run(reader, dialect, |_, _, _| {})?.total),
+        "ansi" => Markup::Ansi,
+        "html" => Markup::Html,
+        other => bail!(
+            "unknown visualization {:?}, expected none, ansi or html",
+            other
+        ),
+    };
+
+    let mut input = Vec::new();
+    reader.read_to_end(&mut input)?;
+    let mut regions = Vec::new();
+    let mut listing = Vec::new();
+    let machine = run(input.as_slice(), dialect, |token, status, machine| {
+        let range = token.offset..token.offset + token.len;
+        listing.push(format!(
+            "{:>8}  {:<16} {:<8} {}",
+            token.offset,
+            String::from_utf8_lossy(&input[range.clone()]),
+            status,
+            machine.total
+        ));
+        regions.push((range, Some(status)));
+    })?;
+
+    println!("{}", render(&input, &regions, markup));
+    println!("{:>8}  {:<16} {:<8} total", "byte", "instruction", "status");
+    for line in listing {
+        println!("{}", line);
+    }
+    Ok(machine.total)
+}
+
+fn main() -> Result<()> {
+    start_day(DAY);
+    let params = Params::from_args(PARAMS)?;
+
+    //region Part 1
+    println!("=== Part 1 ===");
+
+    /// `instructions` plus whatever `params` adds.
+    fn dialect(params: &Params, mut instructions: Vec<Instruction>) -> Result<Dialect> {
+        if params.get("extended")? {
+            instructions.extend([ADD, SUB]);
+        }
+        let max_digits = match params.get::<String>("max_digits")?.as_str() {
+            "any" => None,
+            _ => Some(params.get("max_digits")?),
+        };
+        Ok(Dialect {
+            instructions,
+            max_digits,
+        })
+    }
+
+    fn part1<R: BufRead>(reader: R, params: &Params) -> Result<i64> {
+        execute(reader, &dialect(params, vec![MUL])?, params)
+    }
+
+    assert_eq!(
+        161,
+        part1(BufReader::new(TEST1.as_bytes()), &Params::new(PARAMS))?
+    );
 
-        let sum = operations
-            .fold(OperationResult::Enabled(0), |result, operation| match operation {
-                Operation::Do => OperationResult::Enabled(result.into()),
-                Operation::Dont => OperationResult::Disabled(result.into()),
-                Operation::Mul(mul_call) => match result {
-                    OperationResult::Enabled(v) => OperationResult::Enabled(v + mul_call.call()),
-                    OperationResult::Disabled(_) => result,
-                },
-            })
-            .into();
+    let result = time_snippet!(
+        part1(open_input(INPUT_FILE)?, &params).context(concatcp!("day ", DAY, " part 1"))?
+    );
+    println!("Result = {}", result);
+    //endregion
+
+    //region Part 2
+    println!("\n=== Part 2 ===");
 
-        Ok(sum)
+    fn part2<R: BufRead>(reader: R, params: &Params) -> Result<i64> {
+        let instructions = match params.get("nested")? {
+            true => vec![MUL, NESTED_DO, NESTED_DONT],
+            false => vec![MUL, DO, DONT],
+        };
+        execute(reader, &dialect(params, instructions)?, params)
     }
 
-    assert_eq!(48, part2(BufReader::new(TEST2.as_bytes()))?);
+    assert_eq!(
+        48,
+        part2(BufReader::new(TEST2.as_bytes()), &Params::new(PARAMS))?
+    );
+    assert_eq!(
+        96,
+        part2(BufReader::new(TEST3.as_bytes()), &Params::new(PARAMS))?
+    );
 
-    let input_file = BufReader::new(File::open(INPUT_FILE)?);
-    let result = time_snippet!(part2(input_file)?);
+    let result = time_snippet!(
+        part2(open_input(INPUT_FILE)?, &params).context(concatcp!("day ", DAY, " part 2"))?
+    );
     println!("Result = {}", result);
     //endregion
 
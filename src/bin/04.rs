@@ -1,12 +1,15 @@
+use adv_code_2024::buffer::InputBuffer;
+use adv_code_2024::error::InFile;
+use adv_code_2024::grid::GridView;
+use adv_code_2024::input::line_truncation_warning;
+use adv_code_2024::params::{Param, Params};
 use adv_code_2024::*;
 use anyhow::*;
 use code_timing_macros::time_snippet;
 use const_format::concatcp;
 use itertools::Itertools;
-use std::borrow::BorrowMut;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::ops::Index;
+use std::collections::HashMap;
+use std::fmt::Display;
 
 const DAY: &str = "04";
 const INPUT_FILE: &str = concatcp!("input/", DAY, ".txt");
@@ -24,8 +27,24 @@ MAMMMXMMMM
 MXMXAXMASX
 ";
 
-/*
-part 1:
+// `words` is a comma-separated list to look for in part 1, and `overlaps` lists every cell that
+// more than one of the words found runs through. `overlay` prints the grid with everything but
+// the matched cells blanked out, followed by where each match starts and which way it goes.
+const PARAMS: &[Param] = &[
+    ("words", "XMAS"),
+    ("overlaps", "false"),
+    ("overlay", "false"),
+];
+
+/// Part 2's X-shaped pair of `MAS`es, in one of its orientations; `.` matches anything.
+const X_MAS: &str = "\
+M.S
+.A.
+M.S
+";
+
+// The puzzle's illustrations of which cells of `TEST` the matches cover.
+const PART1_OVERLAY: &str = "\
 ....XXMAS.
 .SAMXMS...
 ...S..A...
@@ -36,8 +55,9 @@ S.S.S.S.SS
 .A.A.A.A.A
 ..M.M.M.MM
 .X.X.XMASX
+";
 
-part 2:
+const PART2_OVERLAY: &str = "\
 .M.S......
 ..A..MSMS.
 .M.S.MAA..
@@ -48,192 +68,393 @@ S.S.S.S.S.
 .A.A.A.A..
 M.M.M.M.M.
 ..........
- */
+";
 
-#[derive(Debug)]
-struct Coord {
-    x: usize,
-    y: usize,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Down,
+    DownRight,
+    Right,
+    UpRight,
+    Up,
+    UpLeft,
+    Left,
+    DownLeft,
 }
 
-impl<T> Index<Coord> for Vec<Vec<T>> {
-    type Output = T;
+impl Direction {
+    const ALL: [Direction; 8] = [
+        Direction::Down,
+        Direction::DownRight,
+        Direction::Right,
+        Direction::UpRight,
+        Direction::Up,
+        Direction::UpLeft,
+        Direction::Left,
+        Direction::DownLeft,
+    ];
 
-    fn index(&self, Coord { x, y }: Coord) -> &Self::Output {
-        &self[y][x]
+    /// Where one step in this direction goes.
+    fn delta(self) -> Delta {
+        let (x, y) = match self {
+            Direction::Down => (0, 1),
+            Direction::DownRight => (1, 1),
+            Direction::Right => (1, 0),
+            Direction::UpRight => (1, -1),
+            Direction::Up => (0, -1),
+            Direction::UpLeft => (-1, -1),
+            Direction::Left => (-1, 0),
+            Direction::DownLeft => (-1, 1),
+        };
+        Delta { x, y }
     }
 }
 
-fn main() -> Result<()> {
-    start_day(DAY);
-
-    //region Part 1
-    println!("=== Part 1 ===");
+impl Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Direction::Down => "down",
+            Direction::DownRight => "down-right",
+            Direction::Right => "right",
+            Direction::UpRight => "up-right",
+            Direction::Up => "up",
+            Direction::UpLeft => "up-left",
+            Direction::Left => "left",
+            Direction::DownLeft => "down-left",
+        };
+        write!(f, "{}", name)
+    }
+}
 
-    fn part1<R: BufRead>(reader: R) -> Result<usize> {
-        let grid: Vec<Vec<char>> = reader
-            .lines()
-            .flatten()
-            .map(|line| line.chars().collect_vec())
-            .collect_vec();
+/// One word found in the grid, spelled out from `start` going `direction`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct WordMatch<'w> {
+    word: &'w str,
+    start: Coord,
+    direction: Direction,
+}
 
-        let x_instances: Vec<Coord> = (&grid)
-            .iter()
-            .enumerate()
-            .flat_map(|(y, line)| {
-                line.iter()
-                    .enumerate()
-                    .filter_map(move |(x, char)| match char {
-                        &'X' => Some(Coord { x, y }),
-                        _ => None,
-                    })
-            })
-            .collect();
+impl WordMatch<'_> {
+    fn cells(&self) -> impl Iterator<Item = Coord> + '_ {
+        (0..self.word.len() as isize).map(|i| self.start + self.direction.delta() * i)
+    }
+}
 
-        enum Direction {
-            Down,
-            DownRight,
-            Right,
-            UpRight,
-            Up,
-            UpLeft,
-            Left,
-            DownLeft,
-        }
+impl Display for WordMatch<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at {} going {}",
+            self.word, self.start, self.direction
+        )
+    }
+}
 
-        fn iter_direction(
-            Coord { x: x_x, y: x_y }: &Coord,
-            grid: &Vec<Vec<char>>,
-            direction: &Direction,
-        ) -> Option<String> {
-            let x_mul = match direction {
-                Direction::Down => 0,
-                Direction::DownRight => 1,
-                Direction::Right => 1,
-                Direction::UpRight => 1,
-                Direction::Up => 0,
-                Direction::UpLeft => -1,
-                Direction::Left => -1,
-                Direction::DownLeft => -1,
-            };
-            let y_mul = match direction {
-                Direction::Down => 1,
-                Direction::DownRight => 1,
-                Direction::Right => 0,
-                Direction::UpRight => -1,
-                Direction::Up => -1,
-                Direction::UpLeft => -1,
-                Direction::Left => 0,
-                Direction::DownLeft => 1,
-            };
-
-            // https://stackoverflow.com/a/54035801
-            fn add(u: usize, i: i32) -> Option<usize> {
-                if i.is_negative() {
-                    u.checked_sub(i.wrapping_abs() as u32 as usize)
-                } else {
-                    u.checked_add(i as usize)
+/// Every place any of `words` can be read in a straight line, in any of the eight directions.
+/// A palindrome is found twice over the same cells, once each way.
+fn find_words<'w>(grid: &GridView, words: &[&'w str]) -> Vec<WordMatch<'w>> {
+    let mut matches = Vec::new();
+    for (start, _) in grid.cells() {
+        for &word in words {
+            for direction in Direction::ALL {
+                let found = word
+                    .bytes()
+                    .zip(0..)
+                    .all(|(byte, i)| grid.get(start + direction.delta() * i) == Some(byte));
+                if found && !word.is_empty() {
+                    matches.push(WordMatch {
+                        word,
+                        start,
+                        direction,
+                    });
                 }
             }
+        }
+    }
+    matches
+}
 
-            let coords = (0..4).map(|offset: i32| {
-                let x = add(*x_x, offset * x_mul)?;
-                let y = add(*x_y, offset * y_mul)?;
-                Some(Coord { x, y })
-            });
-            let all_in_bounds = coords.to_owned().borrow_mut().all(|v| match v {
-                Some(Coord { x, y }) => y < grid.len() && x < grid.first().unwrap().len(),
-                None => false,
-            });
-            if !all_in_bounds {
-                return None;
-            }
-            Some(String::from_iter(coords.map(|coord| grid[coord.unwrap()])))
+/// The cells more than one match runs through, with the indices of those matches.
+fn overlaps(matches: &[WordMatch]) -> Vec<(Coord, Vec<usize>)> {
+    let mut by_cell: HashMap<Coord, Vec<usize>> = HashMap::new();
+    for (i, found) in matches.iter().enumerate() {
+        for coord in found.cells() {
+            by_cell.entry(coord).or_default().push(i);
         }
+    }
+    by_cell
+        .into_iter()
+        .filter(|(_, shared)| shared.len() > 1)
+        .sorted_by_key(|&(Coord { x, y }, _)| (y, x))
+        .collect()
+}
 
-        Ok(x_instances
-            .iter()
-            .map(|coord| {
-                [
-                    Direction::Down,
-                    Direction::DownRight,
-                    Direction::Right,
-                    Direction::UpRight,
-                    Direction::Up,
-                    Direction::UpLeft,
-                    Direction::Left,
-                    Direction::DownLeft,
-                ]
-                .iter()
-                .filter_map(|direction| iter_direction(&coord, &grid, direction))
-                .filter(|str| str == &"XMAS")
-                .count()
-            })
-            .sum())
+/// The grid with every cell not in `cells` replaced by `.`.
+fn overlay(grid: &GridView, cells: impl IntoIterator<Item = Coord>) -> String {
+    let mut rows: Vec<Vec<u8>> = vec![vec![b'.'; grid.width()]; grid.height()];
+    for coord in cells {
+        if let (Some((x, y)), Some(byte)) = (coord.indices(), grid.get(coord)) {
+            rows[y][x] = byte;
+        }
     }
+    rows.iter()
+        .map(|row| format!("{}\n", String::from_utf8_lossy(row)))
+        .collect()
+}
 
-    assert_eq!(18, part1(BufReader::new(TEST.as_bytes()))?);
+/// How a template was turned around to match: mirrored left to right first, then turned
+/// clockwise a quarter at a time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Orientation {
+    reflected: bool,
+    quarter_turns: usize,
+}
 
-    let input_file = BufReader::new(File::open(INPUT_FILE)?);
-    let result = time_snippet!(part1(input_file)?);
-    println!("Result = {}", result);
-    //endregion
+impl Display for Orientation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.reflected, self.quarter_turns) {
+            (false, 0) => write!(f, "as drawn"),
+            (true, 0) => write!(f, "mirrored"),
+            (false, turns) => write!(f, "turned {}° clockwise", turns * 90),
+            (true, turns) => write!(f, "mirrored and turned {}° clockwise", turns * 90),
+        }
+    }
+}
 
-    //region Part 2
-    println!("\n=== Part 2 ===");
+/// A place a template matched, along with the cells it actually pinned down.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TemplateMatch {
+    corner: Coord,
+    orientation: Orientation,
+    cells: Vec<Coord>,
+}
 
-    fn part2<R: BufRead>(reader: R) -> Result<usize> {
-        let grid: Vec<Vec<char>> = reader
-            .lines()
-            .flatten()
-            .map(|line| line.chars().collect_vec())
-            .collect_vec();
+impl Display for TemplateMatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "match at {} {}", self.corner, self.orientation)
+    }
+}
 
-        let a_instances: Vec<Coord> = (&grid)
-            .iter()
-            .enumerate()
-            .flat_map(|(y, line)| {
-                line.iter()
-                    .enumerate()
-                    .filter_map(move |(x, char)| match char {
-                        &'A' => Some(Coord { x, y }),
-                        _ => None,
-                    })
+/// A small grid of bytes to look for, where `None` matches anything.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Template {
+    rows: Vec<Vec<Option<u8>>>,
+}
+
+impl Template {
+    /// One line per row, with `.` as the wildcard.
+    fn parse(text: &str) -> Result<Self> {
+        let rows: Vec<Vec<Option<u8>>> = text
+            .lines()
+            .map(|line| {
+                line.bytes()
+                    .map(|byte| (byte != b'.').then_some(byte))
+                    .collect()
             })
             .collect();
+        let width = rows.first().map_or(0, Vec::len);
+        ensure!(width > 0, "template {:?} is empty", text);
+        ensure!(
+            rows.iter().all(|row| row.len() == width),
+            "template {:?} isn't rectangular",
+            text
+        );
+        Ok(Self { rows })
+    }
+
+    fn width(&self) -> usize {
+        self.rows[0].len()
+    }
+
+    fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Turned a quarter clockwise.
+    fn rotated(&self) -> Self {
+        let rows = (0..self.width())
+            .map(|x| (0..self.height()).rev().map(|y| self.rows[y][x]).collect())
+            .collect();
+        Self { rows }
+    }
 
-        Ok(a_instances
+    /// Mirrored left to right.
+    fn reflected(&self) -> Self {
+        let rows = self
+            .rows
             .iter()
-            .filter(|coord| {
-                let Coord { x, y } = &coord;
-                let neighbors_in_bounds = y >= &1
-                    && y < &(grid.len() - 1)
-                    && x >= &1
-                    && x < &(grid.first().unwrap().len() - 1);
-
-                if !neighbors_in_bounds {
-                    return false;
-                }
+            .map(|row| row.iter().rev().copied().collect())
+            .collect();
+        Self { rows }
+    }
 
-                // \
-                let tl = grid[y - 1][x - 1];
-                let br = grid[y + 1][x + 1];
-                if !((tl == 'M' && br == 'S') || (tl == 'S' && br == 'M')) {
-                    return false;
+    /// The distinct templates reachable by rotating and reflecting this one, itself first.
+    fn orientations(&self) -> Vec<(Orientation, Self)> {
+        let mut orientations: Vec<(Orientation, Self)> = Vec::new();
+        for (reflected, start) in [(false, self.clone()), (true, self.reflected())] {
+            let mut template = start;
+            for quarter_turns in 0..4 {
+                let next = template.rotated();
+                if orientations.iter().all(|(_, seen)| *seen != template) {
+                    let orientation = Orientation {
+                        reflected,
+                        quarter_turns,
+                    };
+                    orientations.push((orientation, template));
                 }
+                template = next;
+            }
+        }
+        orientations
+    }
 
-                // /
-                let bl = grid[y + 1][x - 1];
-                let tr = grid[y - 1][x + 1];
-                return (bl == 'M' && tr == 'S') || (bl == 'S' && tr == 'M');
+    /// Each byte of the template with its offset from the top-left corner.
+    fn offsets(&self) -> impl Iterator<Item = (Delta, Option<u8>)> + '_ {
+        self.rows.iter().zip(0..).flat_map(|(row, y)| {
+            row.iter()
+                .zip(0..)
+                .map(move |(&expected, x)| (Delta { x, y }, expected))
+        })
+    }
+
+    /// The grid cells the template's non-wildcard bytes land on with its corner at `corner`.
+    fn cells(&self, corner: Coord) -> impl Iterator<Item = Coord> + '_ {
+        self.offsets()
+            .filter(|(_, expected)| expected.is_some())
+            .map(move |(offset, _)| corner + offset)
+    }
+
+    /// Whether the template fits on the grid with its top-left corner at `corner`.
+    fn matches_at(&self, grid: &GridView, corner: Coord) -> bool {
+        self.offsets()
+            .all(|(offset, expected)| match grid.get(corner + offset) {
+                Some(byte) => expected.is_none_or(|expected| expected == byte),
+                None => false,
             })
-            .count())
+    }
+}
+
+/// Every place `template` matches, trying each orientation of it when `any_orientation` is set.
+fn find_template(
+    grid: &GridView,
+    template: &Template,
+    any_orientation: bool,
+) -> Vec<TemplateMatch> {
+    let as_drawn = Orientation {
+        reflected: false,
+        quarter_turns: 0,
+    };
+    let orientations = match any_orientation {
+        true => template.orientations(),
+        false => vec![(as_drawn, template.clone())],
+    };
+    let mut matches = Vec::new();
+    for (corner, _) in grid.cells() {
+        for (orientation, oriented) in &orientations {
+            if oriented.matches_at(grid, corner) {
+                matches.push(TemplateMatch {
+                    corner,
+                    orientation: *orientation,
+                    cells: oriented.cells(corner).collect(),
+                });
+            }
+        }
+    }
+    matches
+}
+
+fn main() -> Result<()> {
+    start_day(DAY);
+    let params = Params::from_args(PARAMS)?;
+    let input = InputBuffer::open(INPUT_FILE)?;
+    // Any letters can be searched for, so only the grid's shape is checked.
+    let grid = GridView::new(input.as_bytes()).in_file(INPUT_FILE)?;
+    let ends_with_newline = input.as_bytes().ends_with(b"\n") || input.as_bytes().ends_with(b"\r");
+    if let Some(warning) = line_truncation_warning(grid.rows().map(<[u8]>::len), ends_with_newline)
+    {
+        eprintln!("warning: {}: {}", INPUT_FILE, warning);
+    }
+
+    //region Part 1
+    println!("=== Part 1 ===");
+
+    fn search<'w>(grid: &GridView, words: &'w str) -> Result<Vec<WordMatch<'w>>> {
+        let words = words.split(',').map(str::trim).collect_vec();
+        ensure!(
+            words.iter().all(|word| !word.is_empty()),
+            "can't search for an empty word in {:?}",
+            words.join(",")
+        );
+        Ok(find_words(grid, &words))
+    }
+
+    fn part1(grid: &GridView, params: &Params) -> Result<usize> {
+        let words: String = params.get("words")?;
+        let matches = search(grid, &words)?;
+        if params.get("overlay")? {
+            print!(
+                "{}",
+                overlay(grid, matches.iter().flat_map(WordMatch::cells))
+            );
+            for found in &matches {
+                println!("{}", found);
+            }
+        }
+        if params.get("overlaps")? {
+            for (coord, shared) in overlaps(&matches) {
+                println!(
+                    "{} {} is in {}",
+                    coord,
+                    grid.get(coord).unwrap() as char,
+                    shared.iter().map(|&i| matches[i]).join(", ")
+                );
+            }
+        }
+        Ok(matches.len())
+    }
+
+    let test_grid = GridView::new(TEST.as_bytes())?;
+    assert_eq!(18, part1(&test_grid, &Params::new(PARAMS))?);
+    let matches = search(&test_grid, "XMAS")?;
+    assert_eq!(
+        PART1_OVERLAY,
+        overlay(&test_grid, matches.iter().flat_map(WordMatch::cells))
+    );
+
+    let result = time_snippet!(part1(&grid, &params)
+        .in_file(INPUT_FILE)
+        .context(concatcp!("day ", DAY, " part 1"))?);
+    println!("Result = {}", result);
+    //endregion
+
+    //region Part 2
+    println!("\n=== Part 2 ===");
+
+    fn part2(grid: &GridView, params: &Params) -> Result<usize> {
+        let matches = find_template(grid, &Template::parse(X_MAS)?, true);
+        if params.get("overlay")? {
+            let cells = matches.iter().flat_map(|found| found.cells.iter().copied());
+            print!("{}", overlay(grid, cells));
+            for found in &matches {
+                println!("{}", found);
+            }
+        }
+        Ok(matches.len())
     }
 
-    assert_eq!(9, part2(BufReader::new(TEST.as_bytes()))?);
+    assert_eq!(9, part2(&test_grid, &Params::new(PARAMS))?);
+    let matches = find_template(&test_grid, &Template::parse(X_MAS)?, true);
+    assert_eq!(
+        PART2_OVERLAY,
+        overlay(
+            &test_grid,
+            matches.iter().flat_map(|found| found.cells.clone())
+        )
+    );
 
-    let input_file = BufReader::new(File::open(INPUT_FILE)?);
-    let result = time_snippet!(part2(input_file)?);
+    let result = time_snippet!(part2(&grid, &params)
+        .in_file(INPUT_FILE)
+        .context(concatcp!("day ", DAY, " part 2"))?);
     println!("Result = {}", result);
     //endregion
 
@@ -1,10 +1,12 @@
+use adv_code_2024::error::{AocError, InFile};
+use adv_code_2024::input::{load_checked_input, read_lines};
+use adv_code_2024::parse::Span;
+use adv_code_2024::validate::Rule;
 use adv_code_2024::*;
 use anyhow::*;
 use code_timing_macros::time_snippet;
 use const_format::concatcp;
-use core::panic;
 use std::collections::HashSet;
-use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::result::Result::Ok;
 
@@ -24,6 +26,12 @@ const TEST: &str = "\
 ......#...
 ";
 
+const SHAPE: &[Rule] = &[
+    Rule::Rectangular,
+    Rule::Alphabet(".#^>v<"),
+    Rule::ExactlyOne("^>v<"),
+];
+
 #[derive(std::fmt::Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum GuardPose {
     Up(Coord),
@@ -83,51 +91,56 @@ impl GuardPose {
 
 fn main() -> Result<()> {
     start_day(DAY);
+    let (input, warning) = load_checked_input(INPUT_FILE, SHAPE)?;
+    if let Some(warning) = warning {
+        eprintln!("warning: {}: {}", INPUT_FILE, warning);
+    }
 
     //region Part 1
     println!("=== Part 1 ===");
 
-    fn parse<R: BufRead>(reader: R) -> (Option<GuardPose>, HashSet<Coord>, (usize, usize)) {
-        reader.lines().flatten().enumerate().fold(
-            (None, HashSet::new(), (0, 0)),
-            |(guard, mut obstacles, (old_width, _)), (y, line)| {
-                let mut guard = guard;
-                let chars: Vec<_> = line.chars().collect();
-                let width = chars.len();
-                if y != 0 && width != old_width {
-                    panic!("Non-rectangular areas are not supported!");
-                }
-                chars.iter().enumerate().for_each(|(x, chr)| {
-                    let coord = Coord {
-                        x: x.try_into().unwrap(),
-                        y: y.try_into().unwrap(),
-                    };
-                    if chr == &'#' {
-                        obstacles.insert(coord);
-                    } else {
-                        match GuardPose::from(chr, coord) {
-                            Some(pose) => match guard {
-                                Some(_) => panic!("Multiple guards!"),
-                                None => {
-                                    guard = Some(pose);
-                                }
-                            },
-                            None => {}
-                        }
+    fn parse<R: BufRead>(reader: R) -> Result<(GuardPose, HashSet<Coord>, (usize, usize))> {
+        let lines = read_lines(reader)?;
+
+        let mut guard = None;
+        let mut obstacles = HashSet::new();
+        for (y, line) in lines.iter().enumerate() {
+            for (x, (offset, chr)) in line.char_indices().enumerate() {
+                let coord = Coord {
+                    x: x.try_into()?,
+                    y: y.try_into()?,
+                };
+                if chr == '#' {
+                    obstacles.insert(coord);
+                } else if let Some(pose) = GuardPose::from(&chr, coord) {
+                    if guard.replace(pose).is_some() {
+                        let span = Span {
+                            line: y + 1,
+                            offset,
+                            text: &line[offset..offset + chr.len_utf8()],
+                        };
+                        return Err(span.error("only one guard").into());
                     }
-                });
-                (guard, obstacles, (width, y + 1))
-            },
-        )
+                }
+            }
+        }
+        let guard = guard.ok_or_else(|| AocError::invariant("validated map has no guard"))?;
+        let width = lines.first().map_or(0, |line| line.chars().count());
+        Ok((guard, obstacles, (width, lines.len())))
+    }
+
+    /// Where the guard's patrol ends up.
+    enum Patrol {
+        /// Walked off the map after visiting these positions.
+        Exits(HashSet<Coord>),
+        /// Came back to a position already visited facing the same way.
+        Loops,
     }
 
     fn find_visited(
-        (guard, obstacles, size): (Option<GuardPose>, &HashSet<Coord>, &(usize, usize)),
-    ) -> Result<HashSet<Coord>> {
-        let mut guard = match guard {
-            Some(pose) => pose,
-            None => panic!("No guard in input!"),
-        };
+        (guard, obstacles, size): (GuardPose, &HashSet<Coord>, &(usize, usize)),
+    ) -> Patrol {
+        let mut guard = guard;
 
         fn within(guard: &GuardPose, (width, height): &(usize, usize)) -> bool {
             let position: Coord = guard.position();
@@ -139,10 +152,10 @@ fn main() -> Result<()> {
 
         let mut visited = HashSet::new();
 
-        while within(&guard, &size) {
+        while within(&guard, size) {
             let newly_added = visited.insert(guard);
             if !newly_added {
-                return Err(Error::msg("Loop Detected"));
+                return Patrol::Loops;
             }
             let ahead = guard.see_forward();
             if obstacles.contains(&ahead) {
@@ -152,23 +165,25 @@ fn main() -> Result<()> {
             }
         }
 
-        Ok(HashSet::from_iter(
+        Patrol::Exits(HashSet::from_iter(
             visited.iter().map(|pose| pose.position()),
         ))
     }
 
     fn part1<R: BufRead>(reader: R) -> Result<usize> {
-        let (guard, obstacles, size) = parse(reader);
+        let (guard, obstacles, size) = parse(reader)?;
 
-        let visited = find_visited((guard, &obstacles, &size)).unwrap();
-
-        Ok(visited.len())
+        match find_visited((guard, &obstacles, &size)) {
+            Patrol::Exits(visited) => Ok(visited.len()),
+            Patrol::Loops => bail!(AocError::no_solution("the guard never leaves the map")),
+        }
     }
 
     assert_eq!(41, part1(BufReader::new(TEST.as_bytes()))?);
 
-    let input_file = BufReader::new(File::open(INPUT_FILE)?);
-    let result = time_snippet!(part1(input_file)?);
+    let result = time_snippet!(part1(BufReader::new(input.as_bytes()))
+        .in_file(INPUT_FILE)
+        .context(concatcp!("day ", DAY, " part 1"))?);
     println!("Result = {}", result);
     //endregion
 
@@ -176,35 +191,31 @@ fn main() -> Result<()> {
     println!("\n=== Part 2 ===");
 
     fn part2<R: BufRead>(reader: R) -> Result<usize> {
-        let (guard, obstacles, size) = parse(reader);
+        let (guard, obstacles, size) = parse(reader)?;
 
-        let visited = find_visited((guard, &obstacles, &size)).unwrap();
-
-        let loop_obstacles: HashSet<_> =
-            HashSet::from_iter(visited.iter().filter(|possible_obstacle| {
-                if **possible_obstacle == guard.unwrap().position() {
-                    return false;
-                }
-
-                let mut obstacles = obstacles.to_owned();
-                let newly_added = obstacles.insert(*possible_obstacle.to_owned());
-                if !newly_added {
-                    panic!("Re-added existing obstacle!");
-                }
+        let Patrol::Exits(visited) = find_visited((guard, &obstacles, &size)) else {
+            bail!(AocError::no_solution("the guard never leaves the map"));
+        };
 
-                // let obstacles = obstacles
-                //     .union(&HashSet::from([**possible_obstacle]))
-                //     .map(|v| v.to_owned())
-                //     .collect::<HashSet<_>>();
+        let mut loop_obstacles = HashSet::new();
+        for possible_obstacle in visited {
+            if possible_obstacle == guard.position() {
+                continue;
+            }
 
-                // let chained = obstacles.iter().chain(vec![*possible_obstacle]);
-                // let obstacles = HashSet::from(chained);
+            let mut obstacles = obstacles.to_owned();
+            let newly_added = obstacles.insert(possible_obstacle);
+            if !newly_added {
+                bail!(AocError::invariant(format!(
+                    "the guard walked through the obstacle at ({}, {})",
+                    possible_obstacle.x, possible_obstacle.y
+                )));
+            }
 
-                match find_visited((guard, &obstacles, &size)) {
-                    Ok(_) => false,
-                    Err(_) => true,
-                }
-            }));
+            if let Patrol::Loops = find_visited((guard, &obstacles, &size)) {
+                loop_obstacles.insert(possible_obstacle);
+            }
+        }
 
         // (0..size.1)
         //     .map(|y| {
@@ -235,8 +246,9 @@ fn main() -> Result<()> {
 
     assert_eq!(6, part2(BufReader::new(TEST.as_bytes()))?);
 
-    let input_file = BufReader::new(File::open(INPUT_FILE)?);
-    let result = time_snippet!(part2(input_file)?);
+    let result = time_snippet!(part2(BufReader::new(input.as_bytes()))
+        .in_file(INPUT_FILE)
+        .context(concatcp!("day ", DAY, " part 2"))?);
     println!("Result = {}", result);
     //endregion
 
@@ -1,9 +1,13 @@
+use adv_code_2024::digits::{concat_digits, UnsignedInt};
+use adv_code_2024::error::InFile;
+use adv_code_2024::input::load_input;
+use adv_code_2024::parse::Span;
+use adv_code_2024::pattern::Pattern;
 use adv_code_2024::*;
 use anyhow::*;
 use code_timing_macros::time_snippet;
 use const_format::concatcp;
 use itertools::{chain, Itertools};
-use std::fs::File;
 use std::io::{BufRead, BufReader};
 
 const DAY: &str = "07";
@@ -35,42 +39,38 @@ enum Operation {
 }
 
 impl Operation {
-    fn calc<
-        N: std::ops::Mul<Output = N> + std::ops::Add<Output = N> + std::str::FromStr + ToString,
-    >(
-        self,
-        a: N,
-        b: N,
-    ) -> N
-    where
-        <N as std::str::FromStr>::Err: std::fmt::Debug,
-    {
+    fn calc<N: UnsignedInt>(self, a: N, b: N) -> Option<N> {
         match self {
-            Operation::Identity => b,
-            Operation::Multiply => a * b,
-            Operation::Addition => a + b,
-            Operation::Concatenation => String::from_iter([a.to_string(), b.to_string()])
-                .parse()
-                .unwrap(),
+            Operation::Identity => Some(b),
+            Operation::Multiply => a.checked_mul(b),
+            Operation::Addition => a.checked_add(b),
+            Operation::Concatenation => concat_digits(a, b),
         }
     }
 }
 
 fn main() -> Result<()> {
     start_day(DAY);
+    let (input, warning) = load_input(INPUT_FILE)?;
+    if let Some(warning) = warning {
+        eprintln!("warning: {}: {}", INPUT_FILE, warning);
+    }
 
     //region Part 1
     println!("=== Part 1 ===");
 
-    fn parse<R: BufRead>(reader: R) -> impl Iterator<Item = Equation> {
-        reader.lines().flatten().map(|line| {
-            let (test_val, numbers) = line.split_once(": ").unwrap();
+    fn parse<R: BufRead>(reader: R) -> Result<Vec<Equation>> {
+        let pattern = Pattern::new("{}: {}");
+        reader
+            .lines()
+            .enumerate()
+            .map(|(i, line)| {
+                let line = line?;
+                let (test_val, numbers) = Span::line(i + 1, &line).scan(&pattern)?;
 
-            Equation {
-                test_val: test_val.parse().unwrap(),
-                numbers: numbers.split(' ').map(|num| num.parse().unwrap()).collect(),
-            }
-        })
+                Ok(Equation { test_val, numbers })
+            })
+            .collect()
     }
 
     fn do_calculation(
@@ -89,8 +89,8 @@ fn main() -> Result<()> {
                     let val = numbers
                         .iter()
                         .zip(operations)
-                        .fold(0, |a, (b, op)| op.to_owned().calc(a, b.to_owned()));
-                    &val == test_val
+                        .try_fold(0, |a, (b, op)| op.to_owned().calc(a, b.to_owned()));
+                    val == Some(*test_val)
                 })
             })
             .map(
@@ -103,18 +103,19 @@ fn main() -> Result<()> {
     }
 
     fn part1<R: BufRead>(reader: R) -> Result<usize> {
-        let equations = parse(reader);
+        let equations = parse(reader)?;
 
         Ok(do_calculation(
-            equations,
+            equations.into_iter(),
             vec![Operation::Multiply, Operation::Addition],
         ))
     }
 
     assert_eq!(3749, part1(BufReader::new(TEST.as_bytes()))?);
 
-    let input_file = BufReader::new(File::open(INPUT_FILE)?);
-    let result = time_snippet!(part1(input_file)?);
+    let result = time_snippet!(part1(BufReader::new(input.as_bytes()))
+        .in_file(INPUT_FILE)
+        .context(concatcp!("day ", DAY, " part 1"))?);
     println!("Result = {}", result);
     //endregion
 
@@ -122,10 +123,10 @@ fn main() -> Result<()> {
     println!("\n=== Part 2 ===");
 
     fn part2<R: BufRead>(reader: R) -> Result<usize> {
-        let equations = parse(reader);
+        let equations = parse(reader)?;
 
         Ok(do_calculation(
-            equations,
+            equations.into_iter(),
             vec![
                 Operation::Multiply,
                 Operation::Addition,
@@ -136,8 +137,9 @@ fn main() -> Result<()> {
 
     assert_eq!(11387, part2(BufReader::new(TEST.as_bytes()))?);
 
-    let input_file = BufReader::new(File::open(INPUT_FILE)?);
-    let result = time_snippet!(part2(input_file)?);
+    let result = time_snippet!(part2(BufReader::new(input.as_bytes()))
+        .in_file(INPUT_FILE)
+        .context(concatcp!("day ", DAY, " part 2"))?);
     println!("Result = {}", result);
     //endregion
 
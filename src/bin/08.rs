@@ -1,10 +1,12 @@
+use adv_code_2024::error::InFile;
+use adv_code_2024::input::{load_checked_input, read_lines};
+use adv_code_2024::validate::Rule;
 use adv_code_2024::*;
 use anyhow::*;
 use code_timing_macros::time_snippet;
 use const_format::concatcp;
 use itertools::{chain, Itertools};
 use std::collections::HashMap;
-use std::fs::File;
 use std::io::{BufRead, BufReader};
 
 const DAY: &str = "08";
@@ -25,6 +27,11 @@ const TEST: &str = "\
 ............
 ";
 
+const SHAPE: &[Rule] = &[
+    Rule::Rectangular,
+    Rule::Alphabet(".0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ"),
+];
+
 // const TEST: &str = "\
 // ..........
 // ..........
@@ -51,14 +58,23 @@ const TEST: &str = "\
 // ..........
 // ";
 
+/// Width and height of the map.
+type Size = (usize, usize);
+/// Every antenna's position, grouped by frequency.
+type Antennas = HashMap<char, Vec<Coord>>;
+
 fn main() -> Result<()> {
     start_day(DAY);
+    let (input, warning) = load_checked_input(INPUT_FILE, SHAPE)?;
+    if let Some(warning) = warning {
+        eprintln!("warning: {}: {}", INPUT_FILE, warning);
+    }
 
     //region Part 1
     println!("=== Part 1 ===");
 
-    fn parse<R: BufRead>(reader: R) -> ((usize, usize), HashMap<char, Vec<Coord>>) {
-        let lines: Vec<_> = reader.lines().flatten().collect();
+    fn parse<R: BufRead>(reader: R) -> Result<(Size, Antennas)> {
+        let lines = read_lines(reader)?;
         let height = lines.len();
         let width = lines.first().unwrap().len();
 
@@ -82,7 +98,7 @@ fn main() -> Result<()> {
             })
             .into_group_map();
 
-        ((width, height), antenna_positions)
+        Ok(((width, height), antenna_positions))
     }
 
     fn within_bounds(Coord { x, y }: &Coord, (width, height): (usize, usize)) -> bool {
@@ -90,7 +106,7 @@ fn main() -> Result<()> {
     }
 
     fn part1<R: BufRead>(reader: R) -> Result<usize> {
-        let (bounds, antenna_positions) = parse(reader);
+        let (bounds, antenna_positions) = parse(reader)?;
 
         let antinodes = antenna_positions
             .iter()
@@ -117,8 +133,9 @@ fn main() -> Result<()> {
 
     assert_eq!(14, part1(BufReader::new(TEST.as_bytes()))?);
 
-    let input_file = BufReader::new(File::open(INPUT_FILE)?);
-    let result = time_snippet!(part1(input_file)?);
+    let result = time_snippet!(part1(BufReader::new(input.as_bytes()))
+        .in_file(INPUT_FILE)
+        .context(concatcp!("day ", DAY, " part 1"))?);
     println!("Result = {}", result);
     //endregion
 
@@ -126,7 +143,7 @@ fn main() -> Result<()> {
     println!("\n=== Part 2 ===");
 
     fn part2<R: BufRead>(reader: R) -> Result<usize> {
-        let (bounds, antenna_positions) = parse(reader);
+        let (bounds, antenna_positions) = parse(reader)?;
         let (width, _) = bounds;
 
         let antinodes = antenna_positions
@@ -164,8 +181,9 @@ fn main() -> Result<()> {
 
     assert_eq!(34, part2(BufReader::new(TEST.as_bytes()))?);
 
-    let input_file = BufReader::new(File::open(INPUT_FILE)?);
-    let result = time_snippet!(part2(input_file)?);
+    let result = time_snippet!(part2(BufReader::new(input.as_bytes()))
+        .in_file(INPUT_FILE)
+        .context(concatcp!("day ", DAY, " part 2"))?);
     println!("Result = {}", result);
     //endregion
 
@@ -1,10 +1,12 @@
+use adv_code_2024::error::{AocError, InFile};
+use adv_code_2024::input::{load_checked_input, read_lines};
+use adv_code_2024::validate::Rule;
 use adv_code_2024::*;
 use anyhow::*;
 use code_timing_macros::time_snippet;
 use const_format::concatcp;
 use itertools::Itertools;
 use std::fmt::Display;
-use std::fs::File;
 use std::io::{BufRead, BufReader};
 
 const DAY: &str = "09";
@@ -14,6 +16,8 @@ const TEST: &str = "\
 2333133121414131402
 ";
 
+const SHAPE: &[Rule] = &[Rule::LineCount(1..=1), Rule::Alphabet("0123456789")];
+
 #[derive(Clone)]
 struct Chunk {
     content: Vec<usize>,
@@ -28,13 +32,13 @@ impl Chunk {
                 self.after += 1;
                 Ok(popped)
             }
-            None => Err(Error::msg("No content to pop")),
+            None => Err(AocError::invariant("no content to pop").into()),
         }
     }
 
     fn push_block(&mut self, block: usize) -> Result<()> {
         if self.after == 0 {
-            return Err(Error::msg("Cannot push to full block"));
+            bail!(AocError::invariant("cannot push to a full chunk"));
         }
         self.content.push(block);
         self.after -= 1;
@@ -55,17 +59,21 @@ impl Display for Chunk {
 
 fn main() -> Result<()> {
     start_day(DAY);
+    let (input, warning) = load_checked_input(INPUT_FILE, SHAPE)?;
+    if let Some(warning) = warning {
+        eprintln!("warning: {}: {}", INPUT_FILE, warning);
+    }
 
     //region Part 1
     println!("=== Part 1 ===");
 
-    fn parse<R: BufRead>(reader: R) -> Vec<Chunk> {
-        let contents = reader.lines().flatten().join("");
+    fn parse<R: BufRead>(reader: R) -> Result<Vec<Chunk>> {
+        let contents = read_lines(reader)?.join("");
         let chunk_pairs = contents
             .chars()
             .map(|char| char.to_digit(10).unwrap())
             .chunks(2);
-        chunk_pairs
+        Ok(chunk_pairs
             .into_iter()
             .enumerate()
             .map(|(i, mut chunk)| {
@@ -76,7 +84,7 @@ fn main() -> Result<()> {
                     after: after_option,
                 }
             })
-            .collect_vec()
+            .collect_vec())
     }
 
     fn debug_chunks(chunks: &Vec<Chunk>) {
@@ -116,7 +124,7 @@ fn main() -> Result<()> {
     }
 
     fn part1<R: BufRead>(reader: R) -> Result<usize> {
-        let mut chunks = parse(reader);
+        let mut chunks = parse(reader)?;
 
         fn chunk_has_content(chunk: &Chunk) -> bool {
             chunk.content.len() != 0
@@ -153,7 +161,9 @@ fn main() -> Result<()> {
                 .unwrap();
             last_popped_chunk_position += pop_offset;
 
-            let block = chunk_to_pop.pop_block().unwrap();
+            let block = chunk_to_pop
+                .pop_block()
+                .with_context(|| format!("moving a block out of chunk {}", chunk_to_pop))?;
 
             let (push_offset, chunk_to_push) = chunks
                 .iter_mut()
@@ -162,7 +172,9 @@ fn main() -> Result<()> {
                 .unwrap();
             last_pushed_chunk_position += push_offset;
 
-            chunk_to_push.push_block(block).unwrap();
+            chunk_to_push
+                .push_block(block)
+                .with_context(|| format!("moving a block of file {}", block))?;
             // debug_chunks(&chunks);
         }
 
@@ -171,8 +183,9 @@ fn main() -> Result<()> {
 
     assert_eq!(1928, part1(BufReader::new(TEST.as_bytes()))?);
 
-    let input_file = BufReader::new(File::open(INPUT_FILE)?);
-    let result = time_snippet!(part1(input_file)?);
+    let result = time_snippet!(part1(BufReader::new(input.as_bytes()))
+        .in_file(INPUT_FILE)
+        .context(concatcp!("day ", DAY, " part 1"))?);
     println!("Result = {}", result);
     //endregion
 
@@ -180,7 +193,7 @@ fn main() -> Result<()> {
     println!("\n=== Part 2 ===");
 
     fn part2<R: BufRead>(reader: R) -> Result<usize> {
-        let mut chunks = parse(reader);
+        let mut chunks = parse(reader)?;
 
         fn find_chunk_span_fits(chunks: &Vec<Chunk>, file_to_move: &Chunk) -> Option<usize> {
             chunks
@@ -226,8 +239,20 @@ fn main() -> Result<()> {
                 }
             };
             let content = file_to_move.content.clone();
-            let fitting_chunk = chunks.get_mut(fitting_idx).unwrap();
-            let remaining_after = fitting_chunk.after - content_size;
+            let fitting_chunk = &mut chunks[fitting_idx];
+            let remaining_after = fitting_chunk
+                .after
+                .checked_sub(content_size)
+                .ok_or_else(|| {
+                    AocError::invariant(format!(
+                        "a span of {} can't hold {} blocks",
+                        fitting_chunk.after, content_size
+                    ))
+                })
+                .with_context(|| match content.first() {
+                    Some(id) => format!("moving file {}", id),
+                    None => format!("moving empty chunk {}", idx),
+                })?;
             fitting_chunk.after = 0;
 
             chunks.insert(
@@ -249,8 +274,9 @@ fn main() -> Result<()> {
 
     assert_eq!(2858, part2(BufReader::new(TEST.as_bytes()))?);
 
-    let input_file = BufReader::new(File::open(INPUT_FILE)?);
-    let result = time_snippet!(part2(input_file)?);
+    let result = time_snippet!(part2(BufReader::new(input.as_bytes()))
+        .in_file(INPUT_FILE)
+        .context(concatcp!("day ", DAY, " part 2"))?);
     println!("Result = {}", result);
     //endregion
 
@@ -1,9 +1,11 @@
+use adv_code_2024::error::InFile;
+use adv_code_2024::input::{load_checked_input, read_lines};
+use adv_code_2024::validate::Rule;
 use adv_code_2024::*;
 use anyhow::*;
 use code_timing_macros::time_snippet;
 use const_format::concatcp;
 use itertools::Itertools;
-use std::fs::File;
 use std::io::{BufRead, BufReader};
 
 const DAY: &str = "10";
@@ -20,17 +22,22 @@ const TEST: &str = "\
 10456732
 ";
 
+const SHAPE: &[Rule] = &[Rule::Rectangular, Rule::Alphabet("0123456789")];
+
 fn main() -> Result<()> {
     start_day(DAY);
+    let (input, warning) = load_checked_input(INPUT_FILE, SHAPE)?;
+    if let Some(warning) = warning {
+        eprintln!("warning: {}: {}", INPUT_FILE, warning);
+    }
 
     //region Part 1
     println!("=== Part 1 ===");
 
-    fn parse<R: BufRead>(reader: R) -> (Vec<Coord>, Vec<Vec<u32>>) {
+    fn parse<R: BufRead>(reader: R) -> Result<(Vec<Coord>, Vec<Vec<u32>>)> {
         let mut trailheads: Vec<Coord> = Vec::new();
-        let map = reader
-            .lines()
-            .flatten()
+        let map = read_lines(reader)?
+            .iter()
             .enumerate()
             .map(|(y, line)| {
                 line.chars()
@@ -48,7 +55,7 @@ fn main() -> Result<()> {
                     .collect_vec()
             })
             .collect_vec();
-        (trailheads, map)
+        Ok((trailheads, map))
     }
 
     fn neighbors(here: &Coord, map: &Vec<Vec<u32>>) -> Vec<Coord> {
@@ -92,7 +99,7 @@ fn main() -> Result<()> {
     }
 
     fn part1<R: BufRead>(reader: R) -> Result<usize> {
-        let (trailheads, map) = parse(reader);
+        let (trailheads, map) = parse(reader)?;
         
         fn unique_reachable_peaks(here: &Coord, map: &Vec<Vec<u32>>) -> Vec<Coord> {
             let here_elevation = map[*here];
@@ -121,8 +128,9 @@ fn main() -> Result<()> {
 
     assert_eq!(36, part1(BufReader::new(TEST.as_bytes()))?);
 
-    let input_file = BufReader::new(File::open(INPUT_FILE)?);
-    let result = time_snippet!(part1(input_file)?);
+    let result = time_snippet!(part1(BufReader::new(input.as_bytes()))
+        .in_file(INPUT_FILE)
+        .context(concatcp!("day ", DAY, " part 1"))?);
     println!("Result = {}", result);
     //endregion
 
@@ -130,7 +138,7 @@ fn main() -> Result<()> {
     println!("\n=== Part 2 ===");
 
     fn part2<R: BufRead>(reader: R) -> Result<usize> {
-        let (trailheads, map) = parse(reader);
+        let (trailheads, map) = parse(reader)?;
 
         fn unique_trails(here: &Coord, map: &Vec<Vec<u32>>) -> usize {
             let here_elevation = map[*here];
@@ -157,8 +165,9 @@ fn main() -> Result<()> {
 
     assert_eq!(81, part2(BufReader::new(TEST.as_bytes()))?);
 
-    let input_file = BufReader::new(File::open(INPUT_FILE)?);
-    let result = time_snippet!(part2(input_file)?);
+    let result = time_snippet!(part2(BufReader::new(input.as_bytes()))
+        .in_file(INPUT_FILE)
+        .context(concatcp!("day ", DAY, " part 2"))?);
     println!("Result = {}", result);
     //endregion
 
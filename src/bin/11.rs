@@ -1,37 +1,50 @@
+use adv_code_2024::digits::{digit_count, split_digits};
+use adv_code_2024::input::load_input;
+use adv_code_2024::ints::uints_by_line;
+use adv_code_2024::params::{Example, Param, Params};
 use adv_code_2024::*;
 use anyhow::*;
 use code_timing_macros::time_snippet;
 use const_format::concatcp;
 use itertools::Itertools;
 use std::collections::HashMap;
-use std::fs::File;
 use std::io::{BufRead, BufReader};
 
 const DAY: &str = "11";
 const INPUT_FILE: &str = concatcp!("input/", DAY, ".txt");
 
-const TEST: &str = "\
+const PART1_PARAMS: &[Param] = &[("blinks", "25")];
+const PART2_PARAMS: &[Param] = &[("blinks", "75")];
+
+const EXAMPLE: Example = Example {
+    input: "\
 125 17
-";
+",
+    params: &[("blinks", "25")],
+};
 
 fn main() -> Result<()> {
     start_day(DAY);
+    let part1_params = Params::from_args(PART1_PARAMS)?;
+    let part2_params = Params::from_args(PART2_PARAMS)?;
+    let (input, warning) = load_input(INPUT_FILE)?;
+    if let Some(warning) = warning {
+        eprintln!("warning: {}: {}", INPUT_FILE, warning);
+    }
 
     //region Part 1
     println!("=== Part 1 ===");
 
-    fn parse<R: BufRead>(reader: R) -> HashMap<usize, usize> {
-        HashMap::from_iter(
-            reader
-                .lines()
+    fn parse<R: BufRead>(reader: R) -> Result<HashMap<usize, usize>> {
+        Ok(HashMap::from_iter(
+            uints_by_line::<usize, _>(reader)?
+                .into_iter()
                 .flatten()
-                .join(" ")
-                .split(' ')
-                .map(|val| (val.parse::<usize>().unwrap(), 1))
+                .map(|val| (val, 1))
                 .into_group_map()
                 .iter()
                 .map(|(k, values)| (*k, values.iter().sum())),
-        )
+        ))
     }
 
     fn add_instances_to_key(key: usize, count: usize, map: &mut HashMap<usize, usize>) {
@@ -50,12 +63,9 @@ fn main() -> Result<()> {
             if stone == 0 {
                 add_instances_to_key(1, count, &mut new_count_this_iter);
             } else {
-                let stone_log = stone.ilog10();
-                if stone_log % 2 == 1 {
-                    let split_point = 10usize.pow(stone_log.div_ceil(2));
-
-                    let left = stone / split_point;
-                    let right = stone % split_point;
+                let stone_digits = digit_count(stone);
+                if stone_digits % 2 == 0 {
+                    let (left, right) = split_digits(stone, stone_digits / 2);
 
                     add_instances_to_key(left, count, &mut new_count_this_iter);
                     add_instances_to_key(right, count, &mut new_count_this_iter);
@@ -68,10 +78,10 @@ fn main() -> Result<()> {
         new_count_this_iter
     }
 
-    fn part1<R: BufRead>(reader: R) -> Result<usize> {
-        let mut value_count = parse(reader);
+    fn part1<R: BufRead>(reader: R, params: &Params) -> Result<usize> {
+        let mut value_count = parse(reader)?;
 
-        for _i in 0..25 {
+        for _i in 0..params.get::<usize>("blinks")? {
             // println!("Blink {}: {:?}", _i + 1, value_count);
             value_count = blink(value_count);
         }
@@ -80,20 +90,26 @@ fn main() -> Result<()> {
         Ok(value_count.values().sum())
     }
 
-    assert_eq!(55312, part1(BufReader::new(TEST.as_bytes()))?);
+    assert_eq!(
+        55312,
+        part1(
+            BufReader::new(EXAMPLE.input.as_bytes()),
+            &EXAMPLE.params(PART1_PARAMS)?
+        )?
+    );
 
-    let input_file = BufReader::new(File::open(INPUT_FILE)?);
-    let result = time_snippet!(part1(input_file)?);
+    let result = time_snippet!(part1(BufReader::new(input.as_bytes()), &part1_params)
+        .context(concatcp!("day ", DAY, " part 1"))?);
     println!("Result = {}", result);
     //endregion
 
     //region Part 2
     println!("\n=== Part 2 ===");
 
-    fn part2<R: BufRead>(reader: R) -> Result<usize> {
-        let mut value_count = parse(reader);
+    fn part2<R: BufRead>(reader: R, params: &Params) -> Result<usize> {
+        let mut value_count = parse(reader)?;
 
-        for _i in 0..75 {
+        for _i in 0..params.get::<usize>("blinks")? {
             // println!("Blink {}: {:?}", _i + 1, value_count);
             value_count = blink(value_count);
         }
@@ -102,8 +118,8 @@ fn main() -> Result<()> {
         Ok(value_count.values().sum())
     }
 
-    let input_file = BufReader::new(File::open(INPUT_FILE)?);
-    let result = time_snippet!(part2(input_file)?);
+    let result = time_snippet!(part2(BufReader::new(input.as_bytes()), &part2_params)
+        .context(concatcp!("day ", DAY, " part 2"))?);
     println!("Result = {}", result);
     //endregion
 
@@ -1,17 +1,25 @@
+use adv_code_2024::error::{AocError, InFile};
+use adv_code_2024::input::load_input;
+use adv_code_2024::math::crt;
+use adv_code_2024::params::{Example, Param, Params};
+use adv_code_2024::parse::Span;
+use adv_code_2024::pattern::Pattern;
 use adv_code_2024::*;
 use anyhow::*;
 use code_timing_macros::time_snippet;
 use const_format::concatcp;
 use itertools::Itertools;
 use std::collections::HashMap;
-use std::fs::File;
 use std::io::prelude::*;
 use std::io::{self, BufRead, BufReader};
 
 const DAY: &str = "14";
 const INPUT_FILE: &str = concatcp!("input/", DAY, ".txt");
 
-const TEST: &str = "\
+const PARAMS: &[Param] = &[("width", "101"), ("height", "103")];
+
+const EXAMPLE: Example = Example {
+    input: "\
 p=0,4 v=3,-3
 p=6,3 v=-1,-3
 p=10,3 v=-1,2
@@ -24,7 +32,9 @@ p=9,3 v=2,3
 p=7,3 v=-1,2
 p=2,4 v=2,-3
 p=9,5 v=-3,-3
-";
+",
+    params: &[("width", "11"), ("height", "7")],
+};
 
 #[derive(Debug)]
 struct Robot {
@@ -62,6 +72,11 @@ impl Robot {
 
 fn main() -> Result<()> {
     start_day(DAY);
+    let params = Params::from_args(PARAMS)?;
+    let (input, warning) = load_input(INPUT_FILE)?;
+    if let Some(warning) = warning {
+        eprintln!("warning: {}: {}", INPUT_FILE, warning);
+    }
 
     //region Part 1
     println!("=== Part 1 ===");
@@ -93,26 +108,29 @@ fn main() -> Result<()> {
         }
     }
 
-    fn parse<R: BufRead>(reader: R, (width, height): (usize, usize)) -> Vec<Robot> {
+    fn room_size(params: &Params) -> Result<(usize, usize)> {
+        let (width, height) = (params.get("width")?, params.get("height")?);
+        ensure!(
+            width > 0 && height > 0,
+            "the room must be at least 1x1, found {width}x{height}"
+        );
+        Ok((width, height))
+    }
+
+    fn parse<R: BufRead>(reader: R, (width, height): (usize, usize)) -> Result<Vec<Robot>> {
+        let pattern = Pattern::new("p={},{} v={},{}");
         reader
             .lines()
-            .flatten()
-            .map(|line| {
-                let (position, velocity) = line.split_once(' ').unwrap();
-                let (x, y) = position[2..].split_once(',').unwrap();
-                let (dx, dy) = velocity[2..].split_once(',').unwrap();
-                Robot {
-                    position: Coord {
-                        x: x.parse().unwrap(),
-                        y: y.parse().unwrap(),
-                    },
-                    velocity: Delta {
-                        x: dx.parse().unwrap(),
-                        y: dy.parse().unwrap(),
-                    },
-                }
+            .enumerate()
+            .map(|(i, line)| {
+                let line = line?;
+                let (x, y, dx, dy) = Span::line(i + 1, &line).scan(&pattern)?;
+                Ok(Robot {
+                    position: Coord { x, y },
+                    velocity: Delta { x: dx, y: dy },
+                })
             })
-            .collect_vec()
+            .collect()
     }
 
     fn safety_factor(robots: &Vec<Robot>, (width, height): (usize, usize)) -> usize {
@@ -151,8 +169,9 @@ fn main() -> Result<()> {
             .product()
     }
 
-    fn part1<R: BufRead>(reader: R, (width, height): (usize, usize)) -> Result<usize> {
-        let mut robots = parse(reader, (width, height));
+    fn part1<R: BufRead>(reader: R, params: &Params) -> Result<usize> {
+        let (width, height) = room_size(params)?;
+        let mut robots = parse(reader, (width, height))?;
 
         for _second in 0..100 {
             robots = robots
@@ -167,10 +186,17 @@ fn main() -> Result<()> {
         Ok(sf)
     }
 
-    assert_eq!(12, part1(BufReader::new(TEST.as_bytes()), (11, 7))?);
-
-    let input_file = BufReader::new(File::open(INPUT_FILE)?);
-    let result = time_snippet!(part1(input_file, (101, 103))?);
+    assert_eq!(
+        12,
+        part1(
+            BufReader::new(EXAMPLE.input.as_bytes()),
+            &EXAMPLE.params(PARAMS)?
+        )?
+    );
+
+    let result = time_snippet!(part1(BufReader::new(input.as_bytes()), &params)
+        .in_file(INPUT_FILE)
+        .context(concatcp!("day ", DAY, " part 1"))?);
     println!("Result = {}", result);
     //endregion
 
@@ -189,27 +215,48 @@ fn main() -> Result<()> {
         let _ = stdin.read(&mut [0u8]).unwrap();
     }
 
-    fn part2<R: BufRead>(reader: R, (width, height): (usize, usize)) -> Result<usize> {
-        let mut robots = parse(reader, (width, height));
-
-        for i in 1..10000 {
-            robots = robots
-                .iter()
-                .map(|robot| robot.simulate((width, height)))
-                .collect_vec();
-
-            // debug_robots(&robots, (width, height));
-            // println!("\nIteration {}", i);
-            println!("{}\t{}", i, safety_factor(&robots, (width, height)));
-        }
+    fn spread(values: impl Iterator<Item = isize>) -> isize {
+        // n² times the variance, which keeps everything in integers
+        let (count, sum, sum_of_squares) = values.fold((0, 0, 0), |(count, sum, squares), v| {
+            (count + 1, sum + v, squares + v * v)
+        });
+        count * sum_of_squares - sum * sum
+    }
 
-        Ok(0)
+    fn part2<R: BufRead>(reader: R, params: &Params) -> Result<usize> {
+        let (width, height) = room_size(params)?;
+        let robots = parse(reader, (width, height))?;
+        let (width, height): (isize, isize) = (width.try_into()?, height.try_into()?);
+
+        // x repeats every `width` seconds and y every `height` seconds, so find when each axis
+        // is most tightly clustered and combine the two times.
+        let most_clustered =
+            |period: isize, axis: fn(&Coord) -> isize, step: fn(&Delta) -> isize| {
+                (0..period)
+                    .min_by_key(|t| {
+                        spread(robots.iter().map(|robot| {
+                            (axis(&robot.position) + step(&robot.velocity) * t).rem_euclid(period)
+                        }))
+                    })
+                    .unwrap()
+            };
+        let x_time = most_clustered(width, |c| c.x, |d| d.x);
+        let y_time = most_clustered(height, |c| c.y, |d| d.y);
+
+        let (time, _) = crt::<i64>(&[
+            (x_time.try_into()?, width.try_into()?),
+            (y_time.try_into()?, height.try_into()?),
+        ])
+        .ok_or_else(|| AocError::no_solution("x and y periods never line up"))?;
+
+        Ok(time.try_into()?)
     }
 
-    // assert_eq!(0, part2(BufReader::new(TEST.as_bytes()))?);
+    // assert_eq!(0, part2(BufReader::new(EXAMPLE.input.as_bytes()), &EXAMPLE.params(PARAMS)?)?);
 
-    let input_file = BufReader::new(File::open(INPUT_FILE)?);
-    let result = time_snippet!(part2(input_file, (101, 103))?);
+    let result = time_snippet!(part2(BufReader::new(input.as_bytes()), &params)
+        .in_file(INPUT_FILE)
+        .context(concatcp!("day ", DAY, " part 2"))?);
     println!("Result = {}", result);
     //endregion
 
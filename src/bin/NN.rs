@@ -1,9 +1,9 @@
+use adv_code_2024::input::{load_input, read_lines};
+use adv_code_2024::*;
 use anyhow::*;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
 use code_timing_macros::time_snippet;
 use const_format::concatcp;
-use adv_code_2024::*;
+use std::io::{BufRead, BufReader};
 
 const DAY: &str = "NN"; // TODO: Fill the day
 const INPUT_FILE: &str = concatcp!("input/", DAY, ".txt");
@@ -20,15 +20,20 @@ fn main() -> Result<()> {
 
     fn part1<R: BufRead>(reader: R) -> Result<usize> {
         // TODO: Solve Part 1 of the puzzle
-        let answer = reader.lines().flatten().count();
+        let answer = read_lines(reader)?.len();
         Ok(answer)
     }
 
     // TODO: Set the expected answer for the test input
     assert_eq!(0, part1(BufReader::new(TEST.as_bytes()))?);
 
-    let input_file = BufReader::new(File::open(INPUT_FILE)?);
-    let result = time_snippet!(part1(input_file)?);
+    let (input, warning) = load_input(INPUT_FILE)?;
+    if let Some(warning) = warning {
+        eprintln!("warning: {}: {}", INPUT_FILE, warning);
+    }
+    let result = time_snippet!(
+        part1(BufReader::new(input.as_bytes())).context(concatcp!("day ", DAY, " part 1"))?
+    );
     println!("Result = {}", result);
     //endregion
 
@@ -41,8 +46,7 @@ fn main() -> Result<()> {
     //
     // assert_eq!(0, part2(BufReader::new(TEST.as_bytes()))?);
     //
-    // let input_file = BufReader::new(File::open(INPUT_FILE)?);
-    // let result = time_snippet!(part2(input_file)?);
+    // let result = time_snippet!(part2(BufReader::new(input.as_bytes())).context(concatcp!("day ", DAY, " part 2"))?);
     // println!("Result = {}", result);
     //endregion
 
@@ -0,0 +1,140 @@
+use std::ops::Deref;
+
+use anyhow::{Context, Result};
+
+enum Storage {
+    Owned(Vec<u8>),
+    #[cfg(feature = "mmap")]
+    Mapped(memmap2::Mmap),
+}
+
+/// A whole input file held as raw bytes, so parsers can borrow from it instead of copying lines.
+pub struct InputBuffer {
+    storage: Storage,
+}
+
+impl InputBuffer {
+    /// Reads the whole file into memory in one go.
+    pub fn read(path: &str) -> Result<Self> {
+        let bytes = std::fs::read(path).with_context(|| format!("reading {}", path))?;
+        Ok(Self {
+            storage: Storage::Owned(bytes),
+        })
+    }
+
+    /// Maps the file when the `mmap` feature is on, and reads it otherwise.
+    pub fn open(path: &str) -> Result<Self> {
+        #[cfg(feature = "mmap")]
+        return Self::map(path);
+        #[cfg(not(feature = "mmap"))]
+        return Self::read(path);
+    }
+
+    /// Maps the file instead of reading it. The file mustn't change while the buffer is alive.
+    #[cfg(feature = "mmap")]
+    pub fn map(path: &str) -> Result<Self> {
+        let file = std::fs::File::open(path).with_context(|| format!("opening {}", path))?;
+        // SAFETY: input files are only ever read, and nothing else writes them while a day runs.
+        let map =
+            unsafe { memmap2::Mmap::map(&file) }.with_context(|| format!("mapping {}", path))?;
+        Ok(Self {
+            storage: Storage::Mapped(map),
+        })
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        match &self.storage {
+            Storage::Owned(bytes) => bytes,
+            #[cfg(feature = "mmap")]
+            Storage::Mapped(map) => map,
+        }
+    }
+
+    /// The buffer as text, failing if it isn't UTF-8.
+    pub fn as_str(&self) -> Result<&str> {
+        Ok(std::str::from_utf8(self.as_bytes())?)
+    }
+
+    pub fn lines(&self) -> ByteLines<'_> {
+        byte_lines(self.as_bytes())
+    }
+}
+
+impl Deref for InputBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+/// Iterator over the lines of a byte buffer, see [`byte_lines`].
+#[derive(Debug, Clone)]
+pub struct ByteLines<'a> {
+    rest: &'a [u8],
+}
+
+/// Splits `bytes` into lines without copying, the same way [`crate::input::load_input`] does with
+/// the default options: a leading byte order mark is skipped, `\r\n` and a lone `\r` end a line
+/// just like `\n`, trailing whitespace is trimmed and there's no empty line after a final newline.
+pub fn byte_lines(bytes: &[u8]) -> ByteLines<'_> {
+    ByteLines {
+        rest: bytes.strip_prefix(b"\xef\xbb\xbf").unwrap_or(bytes),
+    }
+}
+
+impl<'a> Iterator for ByteLines<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        let (line, rest) = match self.rest.iter().position(|&b| b == b'\n' || b == b'\r') {
+            Some(end) => {
+                let rest = &self.rest[end + 1..];
+                let rest = match self.rest[end] {
+                    b'\r' => rest.strip_prefix(b"\n").unwrap_or(rest),
+                    _ => rest,
+                };
+                (&self.rest[..end], rest)
+            }
+            None => (self.rest, &self.rest[self.rest.len()..]),
+        };
+        self.rest = rest;
+        Some(line.trim_ascii_end())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_lines_without_copying() {
+        let text = b"\xef\xbb\xbfab \r\n\ncd\ref\t\n";
+        let lines: Vec<&[u8]> = byte_lines(text).collect();
+        assert_eq!(vec![&b"ab"[..], b"", b"cd", b"ef"], lines);
+        assert_eq!(text[3..].as_ptr(), lines[0].as_ptr());
+
+        assert_eq!(
+            vec![&b"no newline"[..]],
+            byte_lines(b"no newline").collect::<Vec<_>>()
+        );
+        assert_eq!(None, byte_lines(b"").next());
+    }
+
+    #[test]
+    #[cfg(feature = "mmap")]
+    fn maps_files() {
+        let path = std::env::temp_dir().join("adv-code-2024-maps-files.txt");
+        std::fs::write(&path, "XMAS\r\nSAMX\r\n").unwrap();
+        let buffer = InputBuffer::map(path.to_str().unwrap()).unwrap();
+        assert_eq!(
+            vec![&b"XMAS"[..], b"SAMX"],
+            buffer.lines().collect::<Vec<_>>()
+        );
+        drop(buffer);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,66 @@
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use anyhow::{Context, Result};
+
+/// Writes comma-separated records, quoting only the fields that need it.
+pub struct CsvWriter<W: Write> {
+    inner: W,
+}
+
+impl CsvWriter<BufWriter<File>> {
+    pub fn create(path: &str) -> Result<Self> {
+        let file = File::create(path).with_context(|| format!("creating {}", path))?;
+        Ok(Self::new(BufWriter::new(file)))
+    }
+}
+
+impl<W: Write> CsvWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self { inner }
+    }
+
+    pub fn record<I>(&mut self, fields: I) -> Result<()>
+    where
+        I: IntoIterator,
+        I::Item: Display,
+    {
+        for (i, field) in fields.into_iter().enumerate() {
+            if i > 0 {
+                write!(self.inner, ",")?;
+            }
+            let field = field.to_string();
+            if field.contains([',', '"', '\n', '\r']) {
+                write!(self.inner, "\"{}\"", field.replace('"', "\"\""))?;
+            } else {
+                write!(self.inner, "{}", field)?;
+            }
+        }
+        writeln!(self.inner)?;
+        Ok(())
+    }
+
+    /// Flushes and hands back the underlying writer.
+    pub fn finish(mut self) -> Result<W> {
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quotes_only_when_needed() {
+        let mut csv = CsvWriter::new(Vec::new());
+        csv.record(["left", "right", "distance"]).unwrap();
+        csv.record([1, 3, 2]).unwrap();
+        csv.record(["a,b", "say \"hi\"", ""]).unwrap();
+        assert_eq!(
+            "left,right,distance\n1,3,2\n\"a,b\",\"say \"\"hi\"\"\",\n",
+            String::from_utf8(csv.finish().unwrap()).unwrap()
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use std::ops::{Div, Rem};
+
+/// Unsigned integer types that can be taken apart and put together by decimal digit.
+pub trait UnsignedInt: Copy + Ord + Div<Output = Self> + Rem<Output = Self> {
+    const ZERO: Self;
+    const TEN: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn checked_pow10(exp: u32) -> Option<Self>;
+    fn ilog10(self) -> u32;
+}
+
+macro_rules! impl_unsigned_int {
+    ($($t:ty),*) => {$(
+        impl UnsignedInt for $t {
+            const ZERO: Self = 0;
+            const TEN: Self = 10;
+
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_add(self, rhs)
+            }
+
+            fn checked_mul(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_mul(self, rhs)
+            }
+
+            fn checked_pow10(exp: u32) -> Option<Self> {
+                <$t>::checked_pow(10, exp)
+            }
+
+            fn ilog10(self) -> u32 {
+                <$t>::ilog10(self)
+            }
+        }
+    )*};
+}
+
+impl_unsigned_int!(u8, u16, u32, u64, u128, usize);
+
+/// Number of decimal digits in `n`. Zero has one digit.
+pub fn digit_count<T: UnsignedInt>(n: T) -> u32 {
+    if n == T::ZERO {
+        1
+    } else {
+        n.ilog10() + 1
+    }
+}
+
+/// Writes `b` after `a`, so `concat_digits(12, 345) == Some(12345)`. `None` on overflow.
+pub fn concat_digits<T: UnsignedInt>(a: T, b: T) -> Option<T> {
+    a.checked_mul(T::checked_pow10(digit_count(b))?)?
+        .checked_add(b)
+}
+
+/// Splits `n` into the digits before and the last `at` digits, so `split_digits(1234, 1) == (123, 4)`.
+pub fn split_digits<T: UnsignedInt>(n: T, at: u32) -> (T, T) {
+    match T::checked_pow10(at) {
+        Some(divisor) => (n / divisor, n % divisor),
+        None => (T::ZERO, n),
+    }
+}
+
+/// The inverse of [`concat_digits`]: `strip_suffix_digits(12345, 345) == Some(12)`.
+pub fn strip_suffix_digits<T: UnsignedInt>(n: T, suffix: T) -> Option<T> {
+    let divisor = T::checked_pow10(digit_count(suffix))?;
+    if n % divisor == suffix {
+        Some(n / divisor)
+    } else {
+        None
+    }
+}
+
+/// Iterates over the decimal digits of `n`, most significant first.
+pub fn digits<T: UnsignedInt>(n: T) -> Digits<T> {
+    Digits {
+        remaining: n,
+        divisor: T::checked_pow10(digit_count(n) - 1).unwrap(),
+    }
+}
+
+pub struct Digits<T> {
+    remaining: T,
+    divisor: T,
+}
+
+impl<T: UnsignedInt> Iterator for Digits<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.divisor == T::ZERO {
+            return None;
+        }
+        let digit = self.remaining / self.divisor;
+        self.remaining = self.remaining % self.divisor;
+        self.divisor = self.divisor / T::TEN;
+        Some(digit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counting_and_concatenating() {
+        assert_eq!(1, digit_count(0u32));
+        assert_eq!(20, digit_count(u64::MAX));
+        assert_eq!(Some(156), concat_digits(15usize, 6));
+        assert_eq!(Some(100), concat_digits(10u32, 0));
+        assert_eq!(None, concat_digits(255u8, 1));
+    }
+
+    #[test]
+    fn splitting_and_stripping() {
+        assert_eq!((10, 0), split_digits(1000usize, 2));
+        assert_eq!((0, 42), split_digits(42u8, 5));
+        assert_eq!(Some(12), strip_suffix_digits(12345u64, 345));
+        assert_eq!(Some(0), strip_suffix_digits(7u64, 7));
+        assert_eq!(None, strip_suffix_digits(12345u64, 456));
+    }
+
+    #[test]
+    fn iterating() {
+        assert_eq!(vec![2, 0, 2, 4], digits(2024u16).collect::<Vec<_>>());
+        assert_eq!(vec![0], digits(0u16).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,231 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::Coord;
+
+/// Union-find over the indices `0..len`, with path compression and union by rank.
+#[derive(Debug, Clone)]
+pub struct DisjointSet {
+    parent: Vec<usize>,
+    rank: Vec<u8>,
+    sets: usize,
+}
+
+impl DisjointSet {
+    pub fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+            rank: vec![0; len],
+            sets: len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Number of disjoint sets remaining.
+    pub fn set_count(&self) -> usize {
+        self.sets
+    }
+
+    pub fn find(&mut self, element: usize) -> usize {
+        let mut root = element;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+
+        // Point everything on the path straight at the root
+        let mut current = element;
+        while self.parent[current] != root {
+            let next = self.parent[current];
+            self.parent[current] = root;
+            current = next;
+        }
+
+        root
+    }
+
+    /// Merges the sets containing `a` and `b`. Returns `false` if they were already joined.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let a = self.find(a);
+        let b = self.find(b);
+        if a == b {
+            return false;
+        }
+
+        let (child, parent) = match self.rank[a].cmp(&self.rank[b]) {
+            std::cmp::Ordering::Less => (a, b),
+            std::cmp::Ordering::Greater => (b, a),
+            std::cmp::Ordering::Equal => {
+                self.rank[a] += 1;
+                (b, a)
+            }
+        };
+        self.parent[child] = parent;
+        self.sets -= 1;
+        true
+    }
+
+    pub fn same_set(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+}
+
+/// A 4-connected group of cells sharing the same value.
+#[derive(Debug, Clone)]
+pub struct Region<T> {
+    pub value: T,
+    pub cells: HashSet<Coord>,
+}
+
+const ORTHOGONAL: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+const DIAGONAL: [(isize, isize); 4] = [(1, -1), (1, 1), (-1, 1), (-1, -1)];
+
+impl<T> Region<T> {
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn contains(&self, coord: &Coord) -> bool {
+        self.cells.contains(coord)
+    }
+
+    pub fn perimeter(&self) -> usize {
+        self.cells
+            .iter()
+            .map(|&Coord { x, y }| {
+                ORTHOGONAL
+                    .iter()
+                    .filter(|(dx, dy)| {
+                        !self.contains(&Coord {
+                            x: x + dx,
+                            y: y + dy,
+                        })
+                    })
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Number of straight fence sides, which is the same as the number of corners.
+    pub fn sides(&self) -> usize {
+        self.cells
+            .iter()
+            .map(|&Coord { x, y }| {
+                DIAGONAL
+                    .iter()
+                    .filter(|(dx, dy)| {
+                        let horizontal = self.contains(&Coord { x: x + dx, y });
+                        let vertical = self.contains(&Coord { x, y: y + dy });
+                        let diagonal = self.contains(&Coord {
+                            x: x + dx,
+                            y: y + dy,
+                        });
+                        // Outer corner, or inner corner
+                        (!horizontal && !vertical) || (horizontal && vertical && !diagonal)
+                    })
+                    .count()
+            })
+            .sum()
+    }
+}
+
+/// Labels the 4-connected regions of equal value in a grid, in row-major order of their first cell.
+/// Returns `None` if the grid is too big for its cells to have a [`Coord`].
+pub fn label_regions<T: PartialEq + Clone>(grid: &[Vec<T>]) -> Option<Vec<Region<T>>> {
+    let offsets: Vec<usize> = grid
+        .iter()
+        .scan(0, |offset, row| {
+            let start = *offset;
+            *offset += row.len();
+            Some(start)
+        })
+        .collect();
+    let cell_count = grid.iter().map(Vec::len).sum();
+    let mut set = DisjointSet::new(cell_count);
+
+    for (y, row) in grid.iter().enumerate() {
+        for (x, value) in row.iter().enumerate() {
+            let here = offsets[y] + x;
+            if row.get(x + 1) == Some(value) {
+                set.union(here, here + 1);
+            }
+            if let Some(below) = grid.get(y + 1) {
+                if below.get(x) == Some(value) {
+                    set.union(here, offsets[y + 1] + x);
+                }
+            }
+        }
+    }
+
+    let mut region_by_root: HashMap<usize, usize> = HashMap::new();
+    let mut regions: Vec<Region<T>> = Vec::new();
+    for (y, row) in grid.iter().enumerate() {
+        for (x, value) in row.iter().enumerate() {
+            let root = set.find(offsets[y] + x);
+            let idx = *region_by_root.entry(root).or_insert_with(|| {
+                regions.push(Region {
+                    value: value.clone(),
+                    cells: HashSet::new(),
+                });
+                regions.len() - 1
+            });
+            regions[idx].cells.insert(Coord::from_indices(x, y)?);
+        }
+    }
+
+    Some(regions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(input: &str) -> Vec<Vec<char>> {
+        input.lines().map(|line| line.chars().collect()).collect()
+    }
+
+    #[test]
+    fn union_and_find() {
+        let mut set = DisjointSet::new(5);
+        assert!(set.union(0, 1));
+        assert!(set.union(3, 4));
+        assert!(!set.union(1, 0));
+        assert!(set.same_set(0, 1));
+        assert!(!set.same_set(1, 3));
+        assert_eq!(3, set.set_count());
+    }
+
+    #[test]
+    fn region_measurements() {
+        let regions = label_regions(&grid("AAAA\nBBCD\nBBCC\nEEEC")).unwrap();
+        let summary: Vec<_> = regions
+            .iter()
+            .map(|r| (r.value, r.area(), r.perimeter(), r.sides()))
+            .collect();
+        assert_eq!(
+            vec![
+                ('A', 4, 10, 4),
+                ('B', 4, 8, 4),
+                ('C', 4, 10, 8),
+                ('D', 1, 4, 4),
+                ('E', 3, 8, 4),
+            ],
+            summary
+        );
+    }
+
+    #[test]
+    fn enclosed_regions_count_inner_sides() {
+        let regions = label_regions(&grid("OOOOO\nOXOXO\nOOOOO\nOXOXO\nOOOOO")).unwrap();
+        assert_eq!(5, regions.len());
+        assert_eq!((21, 36, 20), {
+            let o = &regions[0];
+            (o.area(), o.perimeter(), o.sides())
+        });
+    }
+}
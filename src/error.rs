@@ -0,0 +1,123 @@
+use thiserror::Error;
+
+use crate::parse::ParseError;
+use crate::validate::ValidationError;
+
+/// The ways a day can fail, beyond plain I/O.
+#[derive(Debug, Error)]
+pub enum AocError {
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    #[error(transparent)]
+    Validation(#[from] ValidationError),
+    /// The input is well-formed but has no answer.
+    #[error("no solution: {0}")]
+    NoSolution(String),
+    /// Something the solver relies on turned out not to hold. Always a bug.
+    #[error("internal invariant broken: {0}")]
+    Invariant(String),
+}
+
+impl AocError {
+    pub fn no_solution(reason: impl Into<String>) -> Self {
+        Self::NoSolution(reason.into())
+    }
+
+    pub fn invariant(reason: impl Into<String>) -> Self {
+        Self::Invariant(reason.into())
+    }
+
+    /// Wraps a bare [`ParseError`] or [`ValidationError`] so callers only have to look for an
+    /// `AocError`. Anything else, including an error that already has context, is left as is.
+    pub fn classify(error: anyhow::Error) -> anyhow::Error {
+        if error.chain().nth(1).is_some() {
+            return error;
+        }
+        let error = match error.downcast::<ParseError>() {
+            Ok(parse_error) => return Self::from(parse_error).into(),
+            Err(error) => error,
+        };
+        match error.downcast::<ValidationError>() {
+            Ok(validation_error) => Self::from(validation_error).into(),
+            Err(error) => error,
+        }
+    }
+
+    /// Records which file a parse or validation error came from.
+    pub fn set_file(&mut self, file: &str) {
+        match self {
+            Self::Parse(error) => error.file = Some(file.to_owned()),
+            Self::Validation(error) => error.file = Some(file.to_owned()),
+            Self::NoSolution(_) | Self::Invariant(_) => {}
+        }
+    }
+}
+
+/// Turns a bare [`ParseError`] or [`ValidationError`] into an [`AocError`] and attaches the input
+/// file name to it. Call it before adding any context.
+pub trait InFile<T> {
+    fn in_file(self, file: &str) -> anyhow::Result<T>;
+}
+
+impl<T> InFile<T> for anyhow::Result<T> {
+    fn in_file(self, file: &str) -> anyhow::Result<T> {
+        self.map_err(|error| {
+            let mut error = AocError::classify(error);
+            if let Some(aoc_error) = error.downcast_mut::<AocError>() {
+                aoc_error.set_file(file);
+            }
+            error
+        })
+    }
+}
+
+impl<T> InFile<T> for Result<T, ValidationError> {
+    fn in_file(self, file: &str) -> anyhow::Result<T> {
+        self.map_err(|error| AocError::from(error.in_file(file)).into())
+    }
+}
+
+impl<T> InFile<T> for Result<T, ParseError> {
+    fn in_file(self, file: &str) -> anyhow::Result<T> {
+        self.map_err(|error| AocError::from(error.in_file(file)).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::Span;
+    use anyhow::Context;
+
+    #[test]
+    fn in_file_classifies_parse_errors() {
+        let result: anyhow::Result<()> =
+            Err(Span::line(3, "83 17 5").error("': ' separator").into());
+        let error = result.in_file("input/07.txt").unwrap_err();
+        assert_eq!(
+            "input/07.txt:3:1: expected ': ' separator, found \"83 17 5\"",
+            error.to_string()
+        );
+        assert!(matches!(
+            error.downcast_ref::<AocError>(),
+            Some(AocError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn context_chains_read_outside_in() {
+        let result: anyhow::Result<()> = Err(AocError::invariant("no content to pop").into());
+        let error = result
+            .context("moving file 17")
+            .context("day 09 part 2")
+            .unwrap_err();
+        assert_eq!(
+            "day 09 part 2: moving file 17: internal invariant broken: no content to pop",
+            format!("{:#}", error)
+        );
+        assert!(matches!(
+            error.downcast_ref::<AocError>(),
+            Some(AocError::Invariant(_))
+        ));
+    }
+}
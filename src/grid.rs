@@ -0,0 +1,82 @@
+use crate::buffer::byte_lines;
+use crate::parse::{ParseError, Span};
+use crate::Coord;
+
+/// A rectangular grid of bytes borrowed straight from the input, one row per line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GridView<'a> {
+    rows: Vec<&'a [u8]>,
+    width: usize,
+}
+
+impl<'a> GridView<'a> {
+    /// Fails on the first row that isn't as wide as the first one.
+    pub fn new(bytes: &'a [u8]) -> Result<Self, ParseError> {
+        let rows: Vec<&[u8]> = byte_lines(bytes).collect();
+        let width = rows.first().map_or(0, |row| row.len());
+        if let Some((y, row)) = rows.iter().enumerate().find(|(_, row)| row.len() != width) {
+            let text = String::from_utf8_lossy(row);
+            return Err(Span::line(y + 1, &text).error(format!("a row {} wide", width)));
+        }
+        Ok(Self { rows, width })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Panics if `y` is out of bounds.
+    pub fn row(&self, y: usize) -> &'a [u8] {
+        self.rows[y]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &'a [u8]> + '_ {
+        self.rows.iter().copied()
+    }
+
+    /// The byte at `coord`, or `None` if it's off the grid.
+    pub fn get(&self, coord: Coord) -> Option<u8> {
+        let (x, y) = coord.indices()?;
+        self.rows.get(y)?.get(x).copied()
+    }
+
+    /// Every cell in row-major order.
+    pub fn cells(&self) -> impl Iterator<Item = (Coord, u8)> + '_ {
+        self.rows.iter().enumerate().flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .map(move |(x, &byte)| (Coord::from_indices(x, y).unwrap(), byte))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn borrows_rows_from_the_buffer() {
+        let input = b"XMAS\r\nSAMX\r\n";
+        let grid = GridView::new(input).unwrap();
+        assert_eq!((4, 2), (grid.width(), grid.height()));
+        assert_eq!(b"SAMX", grid.row(1));
+        assert_eq!(input[6..].as_ptr(), grid.row(1).as_ptr());
+        assert_eq!(Some(b'X'), grid.get(Coord { x: 3, y: 1 }));
+        assert_eq!(None, grid.get(Coord { x: -1, y: 0 }));
+        assert_eq!(None, grid.get(Coord { x: 4, y: 0 }));
+        assert_eq!(2, grid.cells().filter(|&(_, b)| b == b'A').count());
+    }
+
+    #[test]
+    fn rejects_ragged_rows() {
+        let error = GridView::new(b"abc\nab\n").unwrap_err();
+        assert_eq!(
+            "<input>:2:1: expected a row 3 wide, found \"ab\"",
+            error.to_string()
+        );
+    }
+}
@@ -0,0 +1,194 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+use anyhow::{Context, Result};
+
+use crate::error::AocError;
+use crate::validate::{validate, Rule};
+
+/// What to do with whitespace at the end of each line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TrailingWhitespace {
+    #[default]
+    Trim,
+    Keep,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct InputOptions {
+    pub trailing_whitespace: TrailingWhitespace,
+}
+
+/// Strips a UTF-8 byte order mark, turns `\r\n` and lone `\r` into `\n`, and applies the
+/// trailing whitespace policy to every line.
+pub fn normalize(text: &str, options: &InputOptions) -> String {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let text = text.replace("\r\n", "\n").replace('\r', "\n");
+    match options.trailing_whitespace {
+        TrailingWhitespace::Keep => text,
+        TrailingWhitespace::Trim => text
+            .split('\n')
+            .map(str::trim_end)
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+/// Describes why normalized input looks like it was cut short, if it does.
+pub fn truncation_warning(text: &str) -> Option<String> {
+    line_truncation_warning(text.lines().map(str::len), text.ends_with('\n'))
+}
+
+/// The same check as [`truncation_warning`] for input that's already been split into lines, so
+/// it doesn't need another pass over the text.
+pub fn line_truncation_warning(
+    line_lengths: impl IntoIterator<Item = usize>,
+    ends_with_newline: bool,
+) -> Option<String> {
+    let lengths: Vec<usize> = line_lengths.into_iter().collect();
+    if lengths.iter().all(|&len| len == 0) {
+        return Some(String::from("input is empty"));
+    }
+    if !ends_with_newline {
+        return Some(String::from(
+            "input doesn't end with a newline, so it may have been cut off",
+        ));
+    }
+
+    if let [rest @ .., second_last, last] = lengths.as_slice() {
+        if !rest.is_empty() && last < second_last && rest.iter().all(|len| len == second_last) {
+            return Some(format!(
+                "last line is {} characters but every other line is {}, so it may have been cut off",
+                last, second_last
+            ));
+        }
+    }
+    None
+}
+
+/// Reads and normalizes an input file with the default options. Also returns the
+/// [`truncation_warning`], if any, for the caller to report.
+pub fn load_input(path: &str) -> Result<(String, Option<String>)> {
+    load_input_with(path, &InputOptions::default())
+}
+
+pub fn load_input_with(path: &str, options: &InputOptions) -> Result<(String, Option<String>)> {
+    let raw = std::fs::read_to_string(path).with_context(|| format!("reading {}", path))?;
+    let text = normalize(&raw, options);
+    let warning = truncation_warning(&text);
+    Ok((text, warning))
+}
+
+/// Like [`load_input`], but fails with a diagnostic if the input breaks any of `rules`.
+pub fn load_checked_input(path: &str, rules: &[Rule]) -> Result<(String, Option<String>)> {
+    let (text, warning) = load_input(path)?;
+    validate(&text, rules).map_err(|error| AocError::from(error.in_file(path)))?;
+    Ok((text, warning))
+}
+
+/// Reads every line as is, stopping at the first I/O error. Meant for text that's already been
+/// through [`load_input`], so nothing is normalized a second time.
+pub fn read_lines<R: BufRead>(reader: R) -> Result<Vec<String>> {
+    Ok(reader.lines().collect::<Result<_, _>>()?)
+}
+
+/// Opens an input file for streaming. Unlike [`load_input`] nothing is read up front, so there's
+/// no truncation warning; pair it with [`for_each_line`].
+pub fn open_input(path: &str) -> Result<BufReader<File>> {
+    let file = File::open(path).with_context(|| format!("opening {}", path))?;
+    Ok(BufReader::new(file))
+}
+
+/// Calls `f` with each 1-based line number and line, reusing one buffer so memory stays constant
+/// however long the input is. Lines are normalized like [`normalize`] with the same `options`,
+/// except that a lone `\r` isn't treated as a line break.
+pub fn for_each_line<R: BufRead>(
+    mut reader: R,
+    options: &InputOptions,
+    mut f: impl FnMut(usize, &str) -> Result<()>,
+) -> Result<()> {
+    let mut buffer = String::new();
+    let mut line_number = 0;
+    loop {
+        buffer.clear();
+        if reader.read_line(&mut buffer)? == 0 {
+            return Ok(());
+        }
+        line_number += 1;
+        let line = match line_number {
+            1 => buffer.strip_prefix('\u{feff}').unwrap_or(&buffer),
+            _ => &buffer,
+        };
+        let line = line
+            .strip_suffix('\n')
+            .map_or(line, |line| line.strip_suffix('\r').unwrap_or(line));
+        match options.trailing_whitespace {
+            TrailingWhitespace::Trim => f(line_number, line.trim_end())?,
+            TrailingWhitespace::Keep => f(line_number, line)?,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_windows_input() {
+        let raw = "\u{feff}..#. \r\n.^..\r\n";
+        assert_eq!("..#.\n.^..\n", normalize(raw, &InputOptions::default()));
+
+        let keep = InputOptions {
+            trailing_whitespace: TrailingWhitespace::Keep,
+        };
+        assert_eq!("a \nb\nc", normalize("a \r\nb\rc", &keep));
+        assert_eq!(
+            vec!["12\t", "34"],
+            read_lines("12\t\r\n34\r\n".as_bytes()).unwrap()
+        );
+    }
+
+    #[test]
+    fn streams_normalized_lines() {
+        let lines = |options: &InputOptions| {
+            let mut seen = Vec::new();
+            for_each_line("\u{feff}12 \r\n\n34".as_bytes(), options, |n, line| {
+                seen.push((n, line.to_owned()));
+                Ok(())
+            })
+            .unwrap();
+            seen
+        };
+        assert_eq!(
+            vec![
+                (1, String::from("12")),
+                (2, String::new()),
+                (3, String::from("34"))
+            ],
+            lines(&InputOptions::default())
+        );
+        let keep = InputOptions {
+            trailing_whitespace: TrailingWhitespace::Keep,
+        };
+        assert_eq!(String::from("12 "), lines(&keep)[0].1);
+
+        let stopped = for_each_line("a\nb\n".as_bytes(), &keep, |n, _| match n {
+            2 => anyhow::bail!("stop"),
+            _ => Ok(()),
+        });
+        assert_eq!("stop", stopped.unwrap_err().to_string());
+    }
+
+    #[test]
+    fn spots_truncation() {
+        assert_eq!(None, truncation_warning("abc\ndef\n"));
+        assert!(truncation_warning("abc\ndef").is_some());
+        assert!(truncation_warning("\n").is_some());
+        assert!(truncation_warning("abc\nabc\nab\n")
+            .unwrap()
+            .starts_with("last line is 2 characters"));
+        assert_eq!(None, line_truncation_warning([3, 3], true));
+        assert!(line_truncation_warning([3, 3], false).is_some());
+        assert!(line_truncation_warning([], true).is_some());
+    }
+}
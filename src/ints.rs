@@ -0,0 +1,136 @@
+use std::io::BufRead;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use crate::parse::{ParseError, Span};
+
+/// Iterator over the integers embedded in a string. See [`ints`] and [`uints`].
+pub struct Ints<'a, T> {
+    span: Span<'a>,
+    pos: usize,
+    signed: bool,
+    output: PhantomData<T>,
+}
+
+impl<T: FromStr> Iterator for Ints<'_, T> {
+    type Item = Result<T, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bytes = self.span.text.as_bytes();
+        let digits_start = self.pos + bytes[self.pos..].iter().position(u8::is_ascii_digit)?;
+        let digits_end = digits_start
+            + bytes[digits_start..]
+                .iter()
+                .position(|b| !b.is_ascii_digit())
+                .unwrap_or(bytes.len() - digits_start);
+
+        // A '-' counts as a sign unless it sits between two numbers, like in a range "3-4"
+        let negative = self.signed
+            && digits_start > 0
+            && bytes[digits_start - 1] == b'-'
+            && (digits_start < 2 || !bytes[digits_start - 2].is_ascii_digit());
+        let start = if negative {
+            digits_start - 1
+        } else {
+            digits_start
+        };
+        self.pos = digits_end;
+
+        let number = self.span.sub(start, digits_end);
+        Some(number.text.parse().map_err(|_| {
+            number.error(format!(
+                "a number that fits in {}",
+                std::any::type_name::<T>()
+            ))
+        }))
+    }
+}
+
+impl<'a> Span<'a> {
+    /// All the integers in the span, whatever separates them. A leading `-` makes a number
+    /// negative. A number that doesn't fit in `T` is an error pointing at it.
+    pub fn ints<T: FromStr>(self) -> Ints<'a, T> {
+        Ints {
+            span: self,
+            pos: 0,
+            signed: true,
+            output: PhantomData,
+        }
+    }
+
+    /// Like [`Span::ints`], but any `-` is treated as a separator.
+    pub fn uints<T: FromStr>(self) -> Ints<'a, T> {
+        Ints {
+            signed: false,
+            ..self.ints()
+        }
+    }
+}
+
+/// All the integers in `text`, which is taken to be line 1. See [`Span::ints`].
+pub fn ints<T: FromStr>(text: &str) -> Ints<'_, T> {
+    Span::line(1, text).ints()
+}
+
+/// Like [`ints`], but any `-` is treated as a separator.
+pub fn uints<T: FromStr>(text: &str) -> Ints<'_, T> {
+    Span::line(1, text).uints()
+}
+
+/// The signed integers on each line of the input.
+pub fn ints_by_line<T: FromStr, R: BufRead>(reader: R) -> anyhow::Result<Vec<Vec<T>>> {
+    reader
+        .lines()
+        .enumerate()
+        .map(|(i, line)| Ok(Span::line(i + 1, &line?).ints().collect::<Result<_, _>>()?))
+        .collect()
+}
+
+/// The unsigned integers on each line of the input.
+pub fn uints_by_line<T: FromStr, R: BufRead>(reader: R) -> anyhow::Result<Vec<Vec<T>>> {
+    reader
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            Ok(Span::line(i + 1, &line?)
+                .uints()
+                .collect::<Result<_, _>>()?)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signed_and_unsigned() {
+        let line = "p=0,4 v=3,-3";
+        assert_eq!(Ok(vec![0, 4, 3, -3]), ints::<i8>(line).collect());
+        assert_eq!(Ok(vec![0, 4, 3, 3]), uints::<u8>(line).collect());
+        assert_eq!(Ok(vec![3, 4, -5]), ints::<i64>("3-4 --5").collect());
+        assert_eq!(0, ints::<u32>("no numbers - here").count());
+    }
+
+    #[test]
+    fn whole_input() {
+        let input = "190: 10 19\n\n3267: 81 40 27\n";
+        assert_eq!(
+            vec![vec![190, 10, 19], vec![], vec![3267, 81, 40, 27]],
+            uints_by_line::<usize, _>(input.as_bytes()).unwrap()
+        );
+    }
+
+    #[test]
+    fn numbers_that_dont_fit_are_errors() {
+        let error = Span::line(4, "1 300")
+            .ints::<u8>()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err();
+        assert_eq!(
+            "<input>:4:3: expected a number that fits in u8, found \"300\"",
+            error.to_string()
+        );
+        assert!(ints::<u32>("a -5").next().unwrap().is_err());
+    }
+}
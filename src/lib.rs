@@ -1,8 +1,26 @@
 use std::{
     collections::HashSet,
+    fmt::Display,
     ops::{Add, Index, Mul, Neg, Sub},
 };
 
+pub mod buffer;
+pub mod csv;
+pub mod digits;
+pub mod disjoint_set;
+pub mod error;
+pub mod grid;
+pub mod input;
+pub mod ints;
+pub mod linalg;
+pub mod math;
+pub mod params;
+pub mod parse;
+pub mod pattern;
+pub mod sections;
+pub mod topo;
+pub mod validate;
+
 pub fn start_day(day: &str) {
     println!("Advent of Code 2024 - Day {:0>2}", day);
 }
@@ -17,6 +35,16 @@ mod tests {
     fn it_works() {
         start_day("00");
     }
+
+    #[test]
+    fn coords_convert_to_indices_only_when_non_negative() {
+        let coord = Coord::from_indices(3, 5).unwrap();
+        assert_eq!(Coord { x: 3, y: 5 }, coord);
+        assert_eq!(Some((3, 5)), coord.indices());
+        assert_eq!(None, Coord { x: -1, y: 5 }.indices());
+        assert_eq!(None, Coord { x: 3, y: -5 }.indices());
+        assert_eq!(None, Coord::from_indices(usize::MAX, 0));
+    }
 }
 
 // My Utilities
@@ -26,6 +54,27 @@ pub struct Coord {
     pub y: isize,
 }
 
+impl Coord {
+    /// The coordinate of column `x` in row `y`, or `None` if either doesn't fit in an `isize`.
+    pub fn from_indices(x: usize, y: usize) -> Option<Self> {
+        Some(Self {
+            x: x.try_into().ok()?,
+            y: y.try_into().ok()?,
+        })
+    }
+
+    /// The `(x, y)` indices of this coordinate, or `None` if either is negative.
+    pub fn indices(self) -> Option<(usize, usize)> {
+        Some((self.x.try_into().ok()?, self.y.try_into().ok()?))
+    }
+}
+
+impl Display for Coord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
 // impl PartialEq for Coord {
 //     fn eq(&self, other: &Self) -> bool {
 //         self.x == other.x && self.y == other.y
@@ -37,9 +86,8 @@ pub struct Coord {
 impl<T> Index<Coord> for Vec<Vec<T>> {
     type Output = T;
 
-    fn index(&self, Coord { x, y }: Coord) -> &Self::Output {
-        let x: usize = x.try_into().unwrap();
-        let y: usize = y.try_into().unwrap();
+    fn index(&self, coord: Coord) -> &Self::Output {
+        let (x, y) = coord.indices().unwrap();
         &self[y][x]
     }
 }
@@ -0,0 +1,350 @@
+use std::fmt::Display;
+use std::ops::{Add, Mul, Neg, Sub};
+
+use crate::math::gcd;
+
+/// An exact rational number, always stored in lowest terms with a positive denominator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fraction {
+    numerator: i128,
+    denominator: i128,
+}
+
+impl Fraction {
+    pub const ZERO: Self = Self {
+        numerator: 0,
+        denominator: 1,
+    };
+    pub const ONE: Self = Self {
+        numerator: 1,
+        denominator: 1,
+    };
+
+    /// Returns `None` for a zero denominator.
+    pub fn new(numerator: i128, denominator: i128) -> Option<Self> {
+        (denominator != 0).then(|| Self::reduced(numerator, denominator))
+    }
+
+    /// Callers must already know `denominator` isn't zero.
+    fn reduced(numerator: i128, denominator: i128) -> Self {
+        let divisor = gcd(numerator, denominator) * denominator.signum();
+        Self {
+            numerator: numerator / divisor,
+            denominator: denominator / divisor,
+        }
+    }
+
+    pub fn numerator(self) -> i128 {
+        self.numerator
+    }
+
+    pub fn denominator(self) -> i128 {
+        self.denominator
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.numerator == 0
+    }
+
+    /// Returns `None` when dividing by zero.
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        Self::new(
+            self.numerator * rhs.denominator,
+            self.denominator * rhs.numerator,
+        )
+    }
+
+    pub fn to_integer(self) -> Option<i128> {
+        match self.denominator {
+            1 => Some(self.numerator),
+            _ => None,
+        }
+    }
+}
+
+impl From<i128> for Fraction {
+    fn from(value: i128) -> Self {
+        Self {
+            numerator: value,
+            denominator: 1,
+        }
+    }
+}
+
+impl Display for Fraction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.denominator {
+            1 => write!(f, "{}", self.numerator),
+            _ => write!(f, "{}/{}", self.numerator, self.denominator),
+        }
+    }
+}
+
+impl Neg for Fraction {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            numerator: -self.numerator,
+            denominator: self.denominator,
+        }
+    }
+}
+
+impl Add for Fraction {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::reduced(
+            self.numerator * rhs.denominator + rhs.numerator * self.denominator,
+            self.denominator * rhs.denominator,
+        )
+    }
+}
+
+impl Sub for Fraction {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl Mul for Fraction {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::reduced(
+            self.numerator * rhs.numerator,
+            self.denominator * rhs.denominator,
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Solution<T> {
+    Unique(T),
+    NoSolution,
+    InfinitelyMany,
+}
+
+impl<T> Solution<T> {
+    pub fn unique(self) -> Option<T> {
+        match self {
+            Solution::Unique(values) => Some(values),
+            _ => None,
+        }
+    }
+}
+
+fn to_fractions<const N: usize>(
+    coefficients: [[i128; N]; N],
+    constants: [i128; N],
+) -> (Vec<Vec<Fraction>>, Vec<Fraction>) {
+    (
+        coefficients
+            .iter()
+            .map(|row| row.iter().map(|&v| v.into()).collect())
+            .collect(),
+        constants.iter().map(|&v| v.into()).collect(),
+    )
+}
+
+fn determinant_2x2([[a, b], [c, d]]: [[i128; 2]; 2]) -> i128 {
+    a * d - b * c
+}
+
+fn determinant_3x3(m: [[i128; 3]; 3]) -> i128 {
+    m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+        - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+        + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+}
+
+fn replace_column<const N: usize>(
+    mut coefficients: [[i128; N]; N],
+    column: usize,
+    constants: [i128; N],
+) -> [[i128; N]; N] {
+    for (row, constant) in coefficients.iter_mut().zip(constants) {
+        row[column] = constant;
+    }
+    coefficients
+}
+
+/// Solves `coefficients * [x, y] = constants` with Cramer's rule.
+pub fn solve_2x2(coefficients: [[i128; 2]; 2], constants: [i128; 2]) -> Solution<[Fraction; 2]> {
+    let det = determinant_2x2(coefficients);
+    if det == 0 {
+        let (matrix, constants) = to_fractions(coefficients, constants);
+        return match gaussian_elimination(&matrix, &constants) {
+            Some(Solution::Unique(_)) => unreachable!("Singular matrix had a unique solution"),
+            Some(Solution::NoSolution) => Solution::NoSolution,
+            Some(Solution::InfinitelyMany) => Solution::InfinitelyMany,
+            None => unreachable!("Square matrix was the wrong shape"),
+        };
+    }
+
+    Solution::Unique(std::array::from_fn(|column| {
+        Fraction::reduced(
+            determinant_2x2(replace_column(coefficients, column, constants)),
+            det,
+        )
+    }))
+}
+
+/// Solves `coefficients * [x, y, z] = constants` with Cramer's rule.
+pub fn solve_3x3(coefficients: [[i128; 3]; 3], constants: [i128; 3]) -> Solution<[Fraction; 3]> {
+    let det = determinant_3x3(coefficients);
+    if det == 0 {
+        let (matrix, constants) = to_fractions(coefficients, constants);
+        return match gaussian_elimination(&matrix, &constants) {
+            Some(Solution::Unique(_)) => unreachable!("Singular matrix had a unique solution"),
+            Some(Solution::NoSolution) => Solution::NoSolution,
+            Some(Solution::InfinitelyMany) => Solution::InfinitelyMany,
+            None => unreachable!("Square matrix was the wrong shape"),
+        };
+    }
+
+    Solution::Unique(std::array::from_fn(|column| {
+        Fraction::reduced(
+            determinant_3x3(replace_column(coefficients, column, constants)),
+            det,
+        )
+    }))
+}
+
+/// Solves a system of any shape by reducing the augmented matrix to reduced row echelon form.
+///
+/// Returns `None` if the rows aren't all the same width or there isn't one constant per row.
+pub fn gaussian_elimination(
+    coefficients: &[Vec<Fraction>],
+    constants: &[Fraction],
+) -> Option<Solution<Vec<Fraction>>> {
+    let unknowns = coefficients.first().map_or(0, Vec::len);
+    if coefficients.len() != constants.len() || coefficients.iter().any(|row| row.len() != unknowns)
+    {
+        return None;
+    }
+    let mut rows: Vec<Vec<Fraction>> = coefficients
+        .iter()
+        .zip(constants)
+        .map(|(row, constant)| row.iter().copied().chain([*constant]).collect())
+        .collect();
+
+    let mut pivot_columns = Vec::new();
+    for column in 0..unknowns {
+        let pivot_row = pivot_columns.len();
+        let Some(found) = (pivot_row..rows.len()).find(|&r| !rows[r][column].is_zero()) else {
+            continue;
+        };
+        rows.swap(pivot_row, found);
+
+        let pivot = rows[pivot_row][column];
+        // The pivot was picked for being non-zero
+        let inverse = Fraction::ONE.checked_div(pivot)?;
+        rows[pivot_row].iter_mut().for_each(|v| *v = *v * inverse);
+        let pivot_values = rows[pivot_row].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            let factor = row[column];
+            if r == pivot_row || factor.is_zero() {
+                continue;
+            }
+            for (value, pivot_value) in row.iter_mut().zip(&pivot_values).skip(column) {
+                *value = *value - factor * *pivot_value;
+            }
+        }
+        pivot_columns.push(column);
+    }
+
+    // A row of all zeroes equal to something non-zero
+    if rows[pivot_columns.len()..]
+        .iter()
+        .any(|row| !row[unknowns].is_zero())
+    {
+        return Some(Solution::NoSolution);
+    }
+    if pivot_columns.len() < unknowns {
+        return Some(Solution::InfinitelyMany);
+    }
+
+    Some(Solution::Unique(
+        rows.iter()
+            .take(unknowns)
+            .map(|row| row[unknowns])
+            .collect(),
+    ))
+}
+
+/// The solution as integers, if every value is a non-negative integer.
+pub fn non_negative_integer_solution(solution: &[Fraction]) -> Option<Vec<i128>> {
+    solution
+        .iter()
+        .map(|value| value.to_integer().filter(|v| *v >= 0))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fraction_arithmetic() {
+        let half = Fraction::new(2, -4).unwrap();
+        let third = Fraction::new(1, 3).unwrap();
+        assert_eq!(Fraction::new(-1, 2), Some(half));
+        assert_eq!(Fraction::ONE, -half - half);
+        assert_eq!("-1/6", (half * third).to_string());
+        assert_eq!(
+            Some((-3).into()),
+            half.checked_div(third).map(|v| v * 2.into())
+        );
+        assert_eq!(None, Fraction::new(1, 0));
+        assert_eq!(None, third.checked_div(Fraction::ZERO));
+    }
+
+    #[test]
+    fn claw_machine() {
+        // Button A: X+94, Y+34; Button B: X+22, Y+67; Prize: X=8400, Y=5400
+        let solution = solve_2x2([[94, 22], [34, 67]], [8400, 5400])
+            .unique()
+            .unwrap();
+        assert_eq!(Some(vec![80, 40]), non_negative_integer_solution(&solution));
+
+        let solution = solve_2x2([[26, 67], [66, 21]], [12748, 12176])
+            .unique()
+            .unwrap();
+        assert_eq!(None, non_negative_integer_solution(&solution));
+    }
+
+    #[test]
+    fn singular_systems() {
+        assert_eq!(
+            Solution::InfinitelyMany,
+            solve_2x2([[1, 2], [2, 4]], [3, 6])
+        );
+        assert_eq!(Solution::NoSolution, solve_2x2([[1, 2], [2, 4]], [3, 7]));
+        assert_eq!(
+            Solution::Unique([1.into(), 2.into(), 3.into()]),
+            solve_3x3([[1, 1, 1], [0, 2, 5], [2, 5, -1]], [6, 19, 9])
+        );
+    }
+
+    #[test]
+    fn overdetermined_elimination() {
+        let rows: Vec<Vec<Fraction>> = vec![
+            vec![1.into(), 1.into()],
+            vec![1.into(), (-1).into()],
+            vec![2.into(), 0.into()],
+        ];
+        let constants: Vec<Fraction> = vec![3.into(), 1.into(), 4.into()];
+        assert_eq!(
+            Some(Solution::Unique(vec![2.into(), 1.into()])),
+            gaussian_elimination(&rows, &constants)
+        );
+        assert_eq!(None, gaussian_elimination(&rows, &constants[..2]));
+        assert_eq!(
+            None,
+            gaussian_elimination(&[vec![1.into(), 1.into()], vec![1.into()]], &constants[..2])
+        );
+    }
+}
@@ -0,0 +1,211 @@
+use std::ops::{Add, Div, Mul, Rem, Sub};
+
+/// Signed integer types the number theory helpers work over.
+pub trait Integer:
+    Copy
+    + Ord
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn abs(self) -> Self;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn rem_euclid(self, modulus: Self) -> Self;
+    /// `(a * b) mod modulus` without overflowing the intermediate product.
+    fn mul_mod(a: Self, b: Self, modulus: Self) -> Self;
+}
+
+impl Integer for i64 {
+    const ZERO: Self = 0;
+    const ONE: Self = 1;
+
+    fn abs(self) -> Self {
+        i64::abs(self)
+    }
+
+    fn checked_mul(self, rhs: Self) -> Option<Self> {
+        i64::checked_mul(self, rhs)
+    }
+
+    fn rem_euclid(self, modulus: Self) -> Self {
+        i64::rem_euclid(self, modulus)
+    }
+
+    fn mul_mod(a: Self, b: Self, modulus: Self) -> Self {
+        (a as i128 * b as i128).rem_euclid(modulus as i128) as i64
+    }
+}
+
+impl Integer for i128 {
+    const ZERO: Self = 0;
+    const ONE: Self = 1;
+
+    fn abs(self) -> Self {
+        i128::abs(self)
+    }
+
+    fn checked_mul(self, rhs: Self) -> Option<Self> {
+        i128::checked_mul(self, rhs)
+    }
+
+    fn rem_euclid(self, modulus: Self) -> Self {
+        i128::rem_euclid(self, modulus)
+    }
+
+    fn mul_mod(a: Self, b: Self, modulus: Self) -> Self {
+        let a = a.rem_euclid(modulus);
+        let b = b.rem_euclid(modulus);
+        if let Some(product) = a.checked_mul(b) {
+            return product % modulus;
+        }
+
+        // Double-and-add, so nothing ever exceeds 2 * modulus
+        let mut result = 0;
+        let mut addend = a;
+        let mut factor = b;
+        while factor > 0 {
+            if factor & 1 == 1 {
+                result = add_mod(result, addend, modulus);
+            }
+            addend = add_mod(addend, addend, modulus);
+            factor >>= 1;
+        }
+        result
+    }
+}
+
+fn add_mod<T: Integer>(a: T, b: T, modulus: T) -> T {
+    // Both operands are already reduced, so only one subtraction is ever needed
+    if a >= modulus - b {
+        a - (modulus - b)
+    } else {
+        a + b
+    }
+}
+
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != T::ZERO {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    if a == T::ZERO || b == T::ZERO {
+        return T::ZERO;
+    }
+    (a / gcd(a, b) * b).abs()
+}
+
+/// Returns `(g, x, y)` such that `a * x + b * y == g == gcd(a, b)`.
+pub fn extended_gcd<T: Integer>(a: T, b: T) -> (T, T, T) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_s, mut s) = (T::ONE, T::ZERO);
+    let (mut old_t, mut t) = (T::ZERO, T::ONE);
+    while r != T::ZERO {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_s, s) = (s, old_s - quotient * s);
+        (old_t, t) = (t, old_t - quotient * t);
+    }
+
+    if old_r < T::ZERO {
+        (T::ZERO - old_r, T::ZERO - old_s, T::ZERO - old_t)
+    } else {
+        (old_r, old_s, old_t)
+    }
+}
+
+/// The inverse of `a` modulo `modulus`, if `a` and `modulus` are coprime.
+pub fn mod_inverse<T: Integer>(a: T, modulus: T) -> Option<T> {
+    let (g, x, _) = extended_gcd(a.rem_euclid(modulus), modulus);
+    if g != T::ONE {
+        return None;
+    }
+    Some(x.rem_euclid(modulus))
+}
+
+/// `base.pow(exp) mod modulus`, for non-negative `exp`.
+pub fn mod_pow<T: Integer>(base: T, exp: T, modulus: T) -> T {
+    let two = T::ONE + T::ONE;
+    let mut result = T::ONE.rem_euclid(modulus);
+    let mut base = base.rem_euclid(modulus);
+    let mut exp = exp;
+    while exp > T::ZERO {
+        if exp % two == T::ONE {
+            result = T::mul_mod(result, base, modulus);
+        }
+        base = T::mul_mod(base, base, modulus);
+        exp = exp / two;
+    }
+    result
+}
+
+/// Solves `x ≡ residue (mod modulus)` for every pair, returning `(x, lcm of moduli)`.
+///
+/// Moduli don't need to be coprime. Returns `None` if the congruences are incompatible, a
+/// modulus isn't positive, or the combined modulus doesn't fit in `T`.
+pub fn crt<T: Integer>(congruences: &[(T, T)]) -> Option<(T, T)> {
+    congruences
+        .iter()
+        .try_fold((T::ZERO, T::ONE), |(residue, modulus), &(r, m)| {
+            if m <= T::ZERO {
+                return None;
+            }
+            let r = r.rem_euclid(m);
+            let g = gcd(modulus, m);
+            let difference = r - residue;
+            if difference % g != T::ZERO {
+                return None;
+            }
+
+            let m_reduced = m / g;
+            let combined = modulus.checked_mul(m_reduced)?;
+            let inverse = mod_inverse(modulus / g, m_reduced)?;
+            let k = T::mul_mod((difference / g).rem_euclid(m_reduced), inverse, m_reduced);
+            // Both terms are already below `combined`, so add them without overflowing
+            let x = add_mod(residue, T::mul_mod(modulus, k, combined), combined);
+            Some((x, combined))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_and_lcm() {
+        assert_eq!(6, gcd(48i64, -18));
+        assert_eq!(144, lcm(48i64, 18));
+        assert_eq!(0, lcm(0i128, 5));
+
+        let (g, x, y) = extended_gcd(240i64, 46);
+        assert_eq!(2, g);
+        assert_eq!(g, 240 * x + 46 * y);
+    }
+
+    #[test]
+    fn modular_arithmetic() {
+        assert_eq!(Some(4), mod_inverse(3i64, 11));
+        assert_eq!(None, mod_inverse(4i64, 8));
+        assert_eq!(445, mod_pow(4i64, 13, 497));
+        assert_eq!(1, mod_pow(2i128, (1 << 61) - 2, (1 << 61) - 1));
+    }
+
+    #[test]
+    fn chinese_remainder() {
+        assert_eq!(Some((23, 105)), crt(&[(2i64, 3), (3, 5), (2, 7)]));
+        assert_eq!(Some((10, 12)), crt(&[(4i64, 6), (2, 4)]));
+        assert_eq!(None, crt(&[(1i64, 4), (2, 6)]));
+        assert_eq!(None, crt(&[(1i64, 0)]));
+        assert_eq!(None, crt(&[(1i64, -3)]));
+        let big = i64::MAX - 1;
+        assert_eq!(Some((big - 1, big)), crt(&[(big - 1, big), (big - 1, big)]));
+    }
+}
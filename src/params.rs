@@ -0,0 +1,154 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+
+/// A named knob a day exposes, with its default value written the way it would be on the
+/// command line.
+pub type Param = (&'static str, &'static str);
+
+/// The values of a day's parameters after defaults, example values and `--param` overrides.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    values: Vec<(&'static str, String)>,
+}
+
+impl Params {
+    pub fn new(defaults: &[Param]) -> Self {
+        Self {
+            values: defaults
+                .iter()
+                .map(|(name, value)| (*name, value.to_string()))
+                .collect(),
+        }
+    }
+
+    /// Reads `--param name=value` overrides from the command line.
+    pub fn from_args(defaults: &[Param]) -> Result<Self> {
+        Self::parse_args(defaults, std::env::args().skip(1))
+    }
+
+    pub fn parse_args(defaults: &[Param], args: impl IntoIterator<Item = String>) -> Result<Self> {
+        let mut params = Self::new(defaults);
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let assignment = match arg.strip_prefix("--param") {
+                Some("") => args.next().context("--param needs a name=value after it")?,
+                Some(rest) if rest.starts_with('=') => rest[1..].to_owned(),
+                _ => bail!("unexpected argument {:?}, expected --param name=value", arg),
+            };
+            let (name, value) = assignment
+                .split_once('=')
+                .with_context(|| format!("expected name=value, found {:?}", assignment))?;
+            params.set(name, value)?;
+        }
+        Ok(params)
+    }
+
+    /// Overrides several values at once, e.g. the ones attached to an [`Example`].
+    pub fn with(mut self, overrides: &[Param]) -> Result<Self> {
+        for (name, value) in overrides {
+            self.set(name, value)?;
+        }
+        Ok(self)
+    }
+
+    pub fn set(&mut self, name: &str, value: &str) -> Result<()> {
+        match self.values.iter_mut().find(|(known, _)| *known == name) {
+            Some((_, slot)) => *slot = value.to_owned(),
+            None => bail!("unknown parameter {:?}, expected one of: {}", name, self),
+        }
+        Ok(())
+    }
+
+    pub fn get<T: FromStr>(&self, name: &str) -> Result<T>
+    where
+        T::Err: Into<anyhow::Error>,
+    {
+        let (_, value) = self
+            .values
+            .iter()
+            .find(|(known, _)| *known == name)
+            .with_context(|| format!("no parameter named {:?}", name))?;
+        value
+            .parse()
+            .map_err(Into::into)
+            .with_context(|| format!("parameter {} can't be {:?}", name, value))
+    }
+}
+
+impl Display for Params {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, (name, value)) in self.values.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}={}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// An example input together with the parameter values it was written for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Example {
+    pub input: &'static str,
+    pub params: &'static [Param],
+}
+
+impl Example {
+    /// The day's defaults with this example's values applied.
+    pub fn params(&self, defaults: &[Param]) -> Result<Params> {
+        Params::new(defaults).with(self.params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEFAULTS: &[Param] = &[("width", "101"), ("height", "103")];
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn overrides_defaults_from_args() {
+        let params =
+            Params::parse_args(DEFAULTS, args(&["--param", "width=11", "--param=height=7"]))
+                .unwrap();
+        assert_eq!(11, params.get::<usize>("width").unwrap());
+        assert_eq!(7, params.get::<i32>("height").unwrap());
+        assert_eq!("width=11, height=7", params.to_string());
+    }
+
+    #[test]
+    fn rejects_bad_args() {
+        let error = Params::parse_args(DEFAULTS, args(&["--param", "blinks=40"])).unwrap_err();
+        assert_eq!(
+            "unknown parameter \"blinks\", expected one of: width=101, height=103",
+            error.to_string()
+        );
+        assert!(Params::parse_args(DEFAULTS, args(&["--param"])).is_err());
+        assert!(Params::parse_args(DEFAULTS, args(&["width=11"])).is_err());
+        let params = Params::parse_args(DEFAULTS, args(&["--param", "width=wide"])).unwrap();
+        assert!(params.get::<usize>("width").is_err());
+        let params = Params::parse_args(DEFAULTS, args(&["--param", "width=-1"])).unwrap();
+        assert_eq!(
+            "parameter width can't be \"-1\"",
+            params.get::<usize>("width").unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn examples_carry_their_own_values() {
+        let example = Example {
+            input: "p=0,4 v=3,-3\n",
+            params: &[("width", "11"), ("height", "7")],
+        };
+        let params = example.params(DEFAULTS).unwrap();
+        assert_eq!(11, params.get::<i64>("width").unwrap());
+        assert_eq!(7, params.get::<i64>("height").unwrap());
+    }
+}
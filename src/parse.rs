@@ -0,0 +1,163 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// A parse failure pointing at the offending text in the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub file: Option<String>,
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based byte column.
+    pub column: usize,
+    pub found: String,
+    pub expected: String,
+}
+
+impl ParseError {
+    pub fn in_file(mut self, file: &str) -> Self {
+        self.file = Some(file.to_owned());
+        self
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: expected {}, found {:?}",
+            self.file.as_deref().unwrap_or("<input>"),
+            self.line,
+            self.column,
+            self.expected,
+            self.found
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A slice of one input line that remembers where it came from, so errors can point at it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span<'a> {
+    pub line: usize,
+    /// 0-based byte offset of `text` within the line.
+    pub offset: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Span<'a> {
+    /// A whole line, numbered from 1.
+    pub fn line(line: usize, text: &'a str) -> Self {
+        Self {
+            line,
+            offset: 0,
+            text,
+        }
+    }
+
+    pub fn error(&self, expected: impl Into<String>) -> ParseError {
+        ParseError {
+            file: None,
+            line: self.line,
+            column: self.offset + 1,
+            found: self.text.to_owned(),
+            expected: expected.into(),
+        }
+    }
+
+    pub(crate) fn sub(&self, start: usize, end: usize) -> Self {
+        Self {
+            line: self.line,
+            offset: self.offset + start,
+            text: &self.text[start..end],
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    pub fn split_once(&self, delimiter: &str) -> Result<(Self, Self), ParseError> {
+        match self.text.find(delimiter) {
+            Some(idx) => Ok((
+                self.sub(0, idx),
+                self.sub(idx + delimiter.len(), self.text.len()),
+            )),
+            None => Err(self.error(format!("'{}' separator", delimiter))),
+        }
+    }
+
+    pub fn split(self, delimiter: &'a str) -> impl Iterator<Item = Self> + 'a {
+        self.text.split(delimiter).scan(0, move |start, part| {
+            let span = self.sub(*start, *start + part.len());
+            *start += part.len() + delimiter.len();
+            Some(span)
+        })
+    }
+
+    /// Splits on runs of whitespace, skipping any at either end.
+    pub fn words(self) -> impl Iterator<Item = Self> + 'a {
+        self.text.split_whitespace().map(move |word| {
+            // `word` borrows from `self.text`, so its pointer gives its position
+            let start = word.as_ptr() as usize - self.text.as_ptr() as usize;
+            self.sub(start, start + word.len())
+        })
+    }
+
+    pub fn strip_prefix(&self, prefix: &str) -> Result<Self, ParseError> {
+        match self.text.strip_prefix(prefix) {
+            Some(_) => Ok(self.sub(prefix.len(), self.text.len())),
+            None => Err(self.error(format!("'{}'", prefix))),
+        }
+    }
+
+    pub fn parse<T: FromStr>(&self) -> Result<T, ParseError> {
+        self.text.parse().map_err(|_| {
+            let type_name = std::any::type_name::<T>();
+            self.error(type_name.rsplit("::").next().unwrap_or(type_name))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locates_errors() {
+        let line = Span::line(42, "190 10 19");
+        let error = line.split_once(": ").unwrap_err().in_file("input/07.txt");
+        assert_eq!(
+            "input/07.txt:42:1: expected ': ' separator, found \"190 10 19\"",
+            error.to_string()
+        );
+
+        let line = Span::line(3, "7 6 x 2");
+        let error = line
+            .split(" ")
+            .map(|level| level.parse::<i32>())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err();
+        assert_eq!(
+            (3, 5, "x"),
+            (error.line, error.column, error.found.as_str())
+        );
+        assert_eq!("i32", error.expected);
+    }
+
+    #[test]
+    fn strips_prefixes() {
+        let (position, velocity) = Span::line(1, "p=0,4 v=3,-3").split_once(" ").unwrap();
+        let (x, y) = position
+            .strip_prefix("p=")
+            .unwrap()
+            .split_once(",")
+            .unwrap();
+        assert_eq!(
+            (0, 4),
+            (x.parse::<i32>().unwrap(), y.parse::<i32>().unwrap())
+        );
+        assert_eq!(8, velocity.strip_prefix("v=").unwrap().offset);
+        assert_eq!(1, velocity.strip_prefix("p=").unwrap_err().line);
+    }
+}
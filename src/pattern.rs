@@ -0,0 +1,233 @@
+use crate::parse::{ParseError, Span};
+
+#[derive(Debug, PartialEq)]
+enum Segment<'p> {
+    Literal(&'p str),
+    /// One or more whitespace characters.
+    Whitespace,
+    Capture,
+}
+
+/// A line pattern, split into its pieces once so it can be matched against every line.
+///
+/// A space in the pattern matches any run of whitespace. Each `{}` extends up to the next piece
+/// of the pattern, or the end of the line.
+#[derive(Debug)]
+pub struct Pattern<'p> {
+    text: &'p str,
+    segments: Vec<Segment<'p>>,
+}
+
+impl<'p> Pattern<'p> {
+    /// Panics if two `{}` follow each other, since there's no telling where the first one ends.
+    pub fn new(text: &'p str) -> Self {
+        let mut segments = Vec::new();
+        let mut rest = text;
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix("{}") {
+                assert!(
+                    segments.last() != Some(&Segment::Capture),
+                    "Pattern {:?} has two captures in a row",
+                    text
+                );
+                segments.push(Segment::Capture);
+                rest = after;
+            } else if rest.starts_with(char::is_whitespace) {
+                segments.push(Segment::Whitespace);
+                rest = rest.trim_start();
+            } else {
+                let end = rest
+                    .find(|c: char| c.is_whitespace() || c == '{')
+                    .unwrap_or(rest.len());
+                // A lone '{' is just a literal character
+                let end = if end == 0 { 1 } else { end };
+                segments.push(Segment::Literal(&rest[..end]));
+                rest = &rest[end..];
+            }
+        }
+        Self { text, segments }
+    }
+
+    pub fn capture_count(&self) -> usize {
+        self.segments
+            .iter()
+            .filter(|segment| **segment == Segment::Capture)
+            .count()
+    }
+}
+
+fn whitespace_len(text: &str) -> usize {
+    text.len() - text.trim_start().len()
+}
+
+impl<'a> Span<'a> {
+    /// Matches the whole span against `pattern` and returns the text of each `{}` in it.
+    pub fn captures(&self, pattern: &Pattern) -> Result<Vec<Span<'a>>, ParseError> {
+        let segments = &pattern.segments;
+        let mut captures = Vec::new();
+        let mut pos = 0;
+
+        for (i, segment) in segments.iter().enumerate() {
+            let rest = self.sub(pos, self.text.len());
+            match segment {
+                Segment::Literal(literal) => {
+                    if !rest.text.starts_with(literal) {
+                        return Err(rest.error(format!("'{}'", literal)));
+                    }
+                    pos += literal.len();
+                }
+                Segment::Whitespace => {
+                    let len = whitespace_len(rest.text);
+                    if len == 0 {
+                        return Err(rest.error("whitespace"));
+                    }
+                    pos += len;
+                }
+                Segment::Capture => {
+                    let len = match segments.get(i + 1) {
+                        None => rest.text.len(),
+                        Some(Segment::Literal(literal)) => rest
+                            .text
+                            .find(literal)
+                            .ok_or_else(|| rest.error(format!("'{}'", literal)))?,
+                        Some(Segment::Whitespace) => rest
+                            .text
+                            .find(char::is_whitespace)
+                            .ok_or_else(|| rest.error("whitespace"))?,
+                        Some(Segment::Capture) => unreachable!(),
+                    };
+                    captures.push(self.sub(pos, pos + len));
+                    pos += len;
+                }
+            }
+        }
+
+        if pos != self.text.len() {
+            return Err(self.sub(pos, self.text.len()).error("end of line"));
+        }
+        Ok(captures)
+    }
+
+    /// Matches the span against `pattern` and parses each `{}` into the matching tuple element.
+    ///
+    /// Panics if the pattern doesn't have one `{}` per tuple element.
+    ///
+    /// ```
+    /// # use adv_code_2024::parse::Span;
+    /// # use adv_code_2024::pattern::Pattern;
+    /// let pattern = Pattern::new("p={},{} v={},{}");
+    /// let robot: (u8, u8, i8, i8) = Span::line(1, "p=0,4 v=3,-3").scan(&pattern).unwrap();
+    /// assert_eq!((0, 4, 3, -3), robot);
+    /// ```
+    pub fn scan<T: FromCaptures>(&self, pattern: &Pattern) -> Result<T, ParseError> {
+        assert_eq!(
+            T::COUNT,
+            pattern.capture_count(),
+            "Pattern {:?} doesn't have one capture per value",
+            pattern.text
+        );
+        T::from_captures(&self.captures(pattern)?)
+    }
+}
+
+/// A value that can be read out of a single captured span.
+pub trait FromSpan: Sized {
+    fn from_span(span: Span) -> Result<Self, ParseError>;
+}
+
+macro_rules! impl_from_span_via_parse {
+    ($($t:ty),*) => {$(
+        impl FromSpan for $t {
+            fn from_span(span: Span) -> Result<Self, ParseError> {
+                span.parse()
+            }
+        }
+    )*};
+}
+
+impl_from_span_via_parse!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64, char, bool, String
+);
+
+/// A whitespace-separated list.
+impl<T: FromSpan> FromSpan for Vec<T> {
+    fn from_span(span: Span) -> Result<Self, ParseError> {
+        span.words().map(T::from_span).collect()
+    }
+}
+
+/// A tuple of values, one per `{}` in a pattern.
+pub trait FromCaptures: Sized {
+    const COUNT: usize;
+
+    fn from_captures(captures: &[Span]) -> Result<Self, ParseError>;
+}
+
+macro_rules! impl_from_captures {
+    ($count:expr; $($t:ident $i:tt),*) => {
+        impl<$($t: FromSpan),*> FromCaptures for ($($t,)*) {
+            const COUNT: usize = $count;
+
+            fn from_captures(captures: &[Span]) -> Result<Self, ParseError> {
+                Ok(($($t::from_span(captures[$i])?,)*))
+            }
+        }
+    };
+}
+
+impl_from_captures!(1; A 0);
+impl_from_captures!(2; A 0, B 1);
+impl_from_captures!(3; A 0, B 1, C 2);
+impl_from_captures!(4; A 0, B 1, C 2, D 3);
+impl_from_captures!(5; A 0, B 1, C 2, D 3, E 4);
+impl_from_captures!(6; A 0, B 1, C 2, D 3, E 4, F 5);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn variable_whitespace_and_lists() {
+        let pair: (usize, usize) = Span::line(1, "3   4").scan(&Pattern::new("{} {}")).unwrap();
+        assert_eq!((3, 4), pair);
+
+        let equation: (usize, Vec<usize>) = Span::line(1, "190: 10  19")
+            .scan(&Pattern::new("{}: {}"))
+            .unwrap();
+        assert_eq!((190, vec![10, 19]), equation);
+    }
+
+    #[test]
+    fn mismatches_point_at_the_problem() {
+        let line = Span::line(7, "p=0,4 w=3,-3");
+        let error = line
+            .scan::<(i32, i32, i32, i32)>(&Pattern::new("p={},{} v={},{}"))
+            .unwrap_err();
+        assert_eq!(
+            (7, 7, "'v='"),
+            (error.line, error.column, error.expected.as_str())
+        );
+
+        let error = Span::line(2, "190: 10 x9")
+            .scan::<(usize, Vec<usize>)>(&Pattern::new("{}: {}"))
+            .unwrap_err();
+        assert_eq!((9, "x9"), (error.column, error.found.as_str()));
+
+        let error = Span::line(2, "3 4 5")
+            .scan::<(u8, u8)>(&Pattern::new("{} {}"))
+            .unwrap_err();
+        assert_eq!((3, "4 5"), (error.column, error.found.as_str()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Pattern \"{} {}\" doesn't have one capture per value")]
+    fn scanning_into_the_wrong_tuple_panics() {
+        let _ = Span::line(1, "3 4").scan::<(u8,)>(&Pattern::new("{} {}"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Pattern \"{}{}\" has two captures in a row")]
+    fn adjacent_captures_panic() {
+        Pattern::new("{}{}");
+    }
+}
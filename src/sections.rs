@@ -0,0 +1,101 @@
+use std::io::BufRead;
+
+use anyhow::{bail, Result};
+
+use crate::parse::Span;
+
+/// A run of non-blank lines from the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    /// 1-based line number of the first line in the section.
+    pub first_line: usize,
+    pub lines: Vec<String>,
+}
+
+impl Section {
+    pub fn spans(&self) -> impl Iterator<Item = Span<'_>> {
+        self.lines
+            .iter()
+            .enumerate()
+            .map(|(i, line)| Span::line(self.first_line + i, line))
+    }
+}
+
+/// Splits the input at blank lines. A run of blank lines counts as one separator and blank lines
+/// at either end are ignored, so every section has at least one line and empty input has none.
+pub fn split_sections(reader: impl BufRead) -> Result<Vec<Section>> {
+    let mut sections: Vec<Section> = Vec::new();
+    let mut in_section = false;
+
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            in_section = false;
+        } else if in_section {
+            sections.last_mut().unwrap().lines.push(line);
+        } else {
+            sections.push(Section {
+                first_line: i + 1,
+                lines: vec![line],
+            });
+            in_section = true;
+        }
+    }
+
+    Ok(sections)
+}
+
+/// Splits the input into exactly `N` sections, for destructuring.
+///
+/// ```
+/// # use adv_code_2024::sections::sections;
+/// let [rules, updates] = sections::<2>("47|53\n97|13\n\n75,47,61\n".as_bytes()).unwrap();
+/// assert_eq!(2, rules.lines.len());
+/// assert_eq!(4, updates.first_line);
+/// ```
+pub fn sections<const N: usize>(reader: impl BufRead) -> Result<[Section; N]> {
+    let sections = split_sections(reader)?;
+    if sections.len() != N {
+        bail!(
+            "expected {} blank-line-separated sections, found {}",
+            N,
+            sections.len()
+        );
+    }
+    Ok(sections.try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_sections() {
+        let shape = |input: &str| {
+            split_sections(input.as_bytes())
+                .unwrap()
+                .iter()
+                .map(|s| (s.first_line, s.lines.len()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(vec![(1, 2), (4, 1)], shape("a\nb\n\nc\n\n\n"));
+        assert_eq!(vec![(1, 1), (4, 1)], shape("a\n\n\nb\n"));
+        assert_eq!(vec![(3, 1)], shape("\n \nb"));
+        assert_eq!(Vec::<(usize, usize)>::new(), shape(""));
+    }
+
+    #[test]
+    fn rejects_missing_sections() {
+        let error = sections::<2>("47|53\n75,47,61\n".as_bytes()).unwrap_err();
+        assert_eq!(
+            "expected 2 blank-line-separated sections, found 1",
+            error.to_string()
+        );
+
+        let error = sections::<2>("\n75,47,61\n".as_bytes()).unwrap_err();
+        assert_eq!(
+            "expected 2 blank-line-separated sections, found 1",
+            error.to_string()
+        );
+    }
+}
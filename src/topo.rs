@@ -0,0 +1,145 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+
+/// Returned when the edges restricted to the requested nodes contain a cycle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleError<N> {
+    /// The nodes of one cycle, in edge order. The first node is not repeated at the end.
+    pub cycle: Vec<N>,
+}
+
+impl<N: Display> Display for CycleError<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "cycle detected: ")?;
+        for node in &self.cycle {
+            write!(f, "{} -> ", node)?;
+        }
+        match self.cycle.first() {
+            Some(first) => write!(f, "{}", first),
+            None => write!(f, "(empty)"),
+        }
+    }
+}
+
+impl<N: Debug + Display> std::error::Error for CycleError<N> {}
+
+/// Orders `nodes` so that for every edge `(a, b)` with both ends in `nodes`, `a` comes before `b`.
+///
+/// Edges touching nodes outside `nodes` are ignored. Ties are broken by the order of `nodes`, and
+/// a node listed more than once is placed only once.
+pub fn topological_sort<N>(nodes: &[N], edges: &[(N, N)]) -> Result<Vec<N>, CycleError<N>>
+where
+    N: Copy + Eq + Hash,
+{
+    let mut included: HashSet<N> = HashSet::new();
+    let nodes: Vec<N> = nodes
+        .iter()
+        .copied()
+        .filter(|node| included.insert(*node))
+        .collect();
+    let mut successors: HashMap<N, Vec<N>> = HashMap::new();
+    let mut predecessors: HashMap<N, Vec<N>> = HashMap::new();
+    let mut in_degree: HashMap<N, usize> = nodes.iter().map(|node| (*node, 0)).collect();
+
+    for (from, to) in edges {
+        if !included.contains(from) || !included.contains(to) {
+            continue;
+        }
+        successors.entry(*from).or_default().push(*to);
+        predecessors.entry(*to).or_default().push(*from);
+        *in_degree.get_mut(to).unwrap() += 1;
+    }
+
+    let mut ready: VecDeque<N> = nodes
+        .iter()
+        .filter(|node| in_degree[node] == 0)
+        .copied()
+        .collect();
+    let mut sorted = Vec::with_capacity(in_degree.len());
+
+    while let Some(node) = ready.pop_front() {
+        sorted.push(node);
+        for next in successors.get(&node).into_iter().flatten() {
+            let degree = in_degree.get_mut(next).unwrap();
+            *degree -= 1;
+            if *degree == 0 {
+                ready.push_back(*next);
+            }
+        }
+    }
+
+    if sorted.len() == in_degree.len() {
+        return Ok(sorted);
+    }
+
+    // Every node left over still has a predecessor that is also left over, so walking
+    // backwards from any of them must eventually revisit a node.
+    let remaining: HashSet<N> = in_degree
+        .iter()
+        .filter(|(_, degree)| **degree > 0)
+        .map(|(node, _)| *node)
+        .collect();
+    let start = *nodes.iter().find(|node| remaining.contains(node)).unwrap();
+    let mut path = vec![start];
+    let mut seen_at: HashMap<N, usize> = HashMap::from([(start, 0)]);
+    loop {
+        let current = *path.last().unwrap();
+        let previous = *predecessors[&current]
+            .iter()
+            .find(|node| remaining.contains(node))
+            .unwrap();
+        if let Some(&idx) = seen_at.get(&previous) {
+            let mut cycle = path.split_off(idx);
+            cycle.reverse();
+            cycle.rotate_right(1);
+            return Err(CycleError { cycle });
+        }
+        seen_at.insert(previous, path.len());
+        path.push(previous);
+    }
+}
+
+/// Checks whether `sequence` respects every edge whose ends both appear in it.
+pub fn is_linear_extension<N>(sequence: &[N], edges: &[(N, N)]) -> bool
+where
+    N: Eq + Hash,
+{
+    let position: HashMap<&N, usize> = sequence.iter().enumerate().map(|(i, n)| (n, i)).collect();
+    edges
+        .iter()
+        .all(|(from, to)| match (position.get(from), position.get(to)) {
+            (Some(from), Some(to)) => from < to,
+            _ => true,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorts_subset() {
+        let edges = [(1, 3), (4, 1), (3, 5), (5, 2)];
+        assert_eq!(Ok(vec![4, 1, 3]), topological_sort(&[3, 1, 4], &edges));
+        assert!(is_linear_extension(&[4, 1, 3], &edges));
+        assert!(!is_linear_extension(&[1, 4, 3], &edges));
+    }
+
+    #[test]
+    fn places_repeated_nodes_once() {
+        let edges = [(1, 3), (4, 1)];
+        assert_eq!(
+            Ok(vec![4, 1, 3]),
+            topological_sort(&[3, 1, 4, 1, 4], &edges)
+        );
+    }
+
+    #[test]
+    fn reports_cycle() {
+        let edges = [(1, 2), (2, 3), (3, 1), (0, 1)];
+        let err = topological_sort(&[0, 1, 2, 3], &edges).unwrap_err();
+        assert_eq!(vec![1, 2, 3], err.cycle);
+        assert_eq!("cycle detected: 1 -> 2 -> 3 -> 1", err.to_string());
+    }
+}
@@ -0,0 +1,203 @@
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+
+use crate::parse::Span;
+
+/// One thing a day expects to be true of its input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rule {
+    /// Every line has the same number of characters.
+    Rectangular,
+    /// Only these characters appear.
+    Alphabet(&'static str),
+    /// Exactly one character from this set appears, exactly once.
+    ExactlyOne(&'static str),
+    LineCount(RangeInclusive<usize>),
+    /// Every integer in the input falls in this range.
+    NumbersIn(RangeInclusive<i64>),
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Rule::Rectangular => write!(f, "rectangular"),
+            Rule::Alphabet(chars) => write!(f, "only {:?}", chars),
+            Rule::ExactlyOne(chars) => write!(f, "exactly one of {:?}", chars),
+            Rule::LineCount(range) => write!(f, "{}..={} lines", range.start(), range.end()),
+            Rule::NumbersIn(range) => write!(f, "numbers in {}..={}", range.start(), range.end()),
+        }
+    }
+}
+
+/// A broken [`Rule`], pointing at where it broke if that's a single place.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub rule: Rule,
+    /// 1-based line number.
+    pub line: Option<usize>,
+    /// 1-based character column.
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    pub file: Option<String>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl ValidationError {
+    pub fn in_file(mut self, file: &str) -> Self {
+        self.file = Some(file.to_owned());
+        self
+    }
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let file = self.file.as_deref().unwrap_or("<input>");
+        write!(f, "{} doesn't have the expected shape", file)?;
+        for Diagnostic {
+            rule,
+            line,
+            column,
+            message,
+        } in &self.diagnostics
+        {
+            write!(f, "\n  {}", file)?;
+            if let Some(line) = line {
+                write!(f, ":{}", line)?;
+            }
+            if let Some(column) = column {
+                write!(f, ":{}", column)?;
+            }
+            write!(f, ": expected {}: {}", rule, message)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+fn check(text: &str, rule: &Rule) -> Option<Diagnostic> {
+    let diagnostic = |line: Option<usize>, column: Option<usize>, message: String| Diagnostic {
+        rule: rule.clone(),
+        line,
+        column,
+        message,
+    };
+    let lines = || text.lines().enumerate().map(|(i, line)| (i + 1, line));
+    let chars = || {
+        lines().flat_map(|(line_number, line)| {
+            line.chars()
+                .enumerate()
+                .map(move |(i, c)| (line_number, i + 1, c))
+        })
+    };
+
+    match rule {
+        Rule::Rectangular => {
+            let width = text.lines().next()?.chars().count();
+            lines()
+                .map(|(line_number, line)| (line_number, line.chars().count()))
+                .find(|(_, len)| *len != width)
+                .map(|(line_number, len)| {
+                    diagnostic(
+                        Some(line_number),
+                        None,
+                        format!("line is {} wide but line 1 is {}", len, width),
+                    )
+                })
+        }
+        Rule::Alphabet(allowed) => {
+            chars()
+                .find(|(_, _, c)| !allowed.contains(*c))
+                .map(|(line, column, c)| {
+                    diagnostic(Some(line), Some(column), format!("found {:?}", c))
+                })
+        }
+        Rule::ExactlyOne(wanted) => {
+            let found: Vec<_> = chars().filter(|(_, _, c)| wanted.contains(*c)).collect();
+            match found.as_slice() {
+                [_] => None,
+                [] => Some(diagnostic(None, None, String::from("found none"))),
+                [_, (line, column, _), ..] => Some(diagnostic(
+                    Some(*line),
+                    Some(*column),
+                    format!("found {}, this is the second", found.len()),
+                )),
+            }
+        }
+        Rule::LineCount(range) => {
+            let count = text.lines().count();
+            (!range.contains(&count)).then(|| diagnostic(None, None, format!("found {}", count)))
+        }
+        Rule::NumbersIn(range) => lines().find_map(|(line_number, line)| {
+            Span::line(line_number, line)
+                .ints::<i64>()
+                .find_map(|n| match n {
+                    Ok(n) if range.contains(&n) => None,
+                    Ok(n) => Some(diagnostic(Some(line_number), None, format!("found {}", n))),
+                    Err(error) => Some(diagnostic(
+                        Some(line_number),
+                        Some(error.column),
+                        format!("found {}, which is too large", error.found),
+                    )),
+                })
+        }),
+    }
+}
+
+/// Checks `text` against every rule, reporting all the ones it breaks.
+pub fn validate(text: &str, rules: &[Rule]) -> Result<(), ValidationError> {
+    let diagnostics: Vec<_> = rules.iter().filter_map(|rule| check(text, rule)).collect();
+    if diagnostics.is_empty() {
+        return Ok(());
+    }
+    Err(ValidationError {
+        file: None,
+        diagnostics,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GUARD_MAP: &[Rule] = &[
+        Rule::Rectangular,
+        Rule::Alphabet(".#^>v<"),
+        Rule::ExactlyOne("^>v<"),
+        Rule::LineCount(1..=130),
+    ];
+
+    #[test]
+    fn accepts_matching_input() {
+        assert_eq!(Ok(()), validate("..#\n.^.\n", GUARD_MAP));
+        assert_eq!(
+            Ok(()),
+            validate("3 -4\n", &[Rule::NumbersIn(-5..=5), Rule::LineCount(1..=1)])
+        );
+    }
+
+    #[test]
+    fn reports_every_broken_rule() {
+        let error = validate("..#\n.^x.\n>..\n", GUARD_MAP)
+            .unwrap_err()
+            .in_file("input/06.txt");
+        assert_eq!(
+            "input/06.txt doesn't have the expected shape\n  \
+             input/06.txt:2: expected rectangular: line is 4 wide but line 1 is 3\n  \
+             input/06.txt:2:3: expected only \".#^>v<\": found 'x'\n  \
+             input/06.txt:3:1: expected exactly one of \"^>v<\": found 2, this is the second",
+            error.to_string()
+        );
+
+        let error = validate("1\n2 99999999999999999999\n", &[Rule::NumbersIn(0..=9)]).unwrap_err();
+        assert_eq!(
+            "<input> doesn't have the expected shape\n  \
+             <input>:2:3: expected numbers in 0..=9: found 99999999999999999999, which is too large",
+            error.to_string()
+        );
+    }
+}